@@ -1,37 +1,94 @@
 use std::fmt::Display;
 
-use crate::components::Component;
+use crate::components::{Component, Fallback};
+use crate::runtime::NameFilter;
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug)]
 pub(crate) enum Action {
     Down,
     Up,
     PageUp,
     PageDown,
+    Top,
+    Bottom,
     Quit,
     All,
     Inspect,
     Logs,
     Shell,
     Delete,
+    Select,
+    Prune,
+    Pull,
+    Commit,
+    Save,
+    Load,
+    PullCredentials(String),
     Screen(Component),
+    // Back to the screen left for the current one, or to this one when there is none
+    Back(Fallback),
     Ok,
     PreviousScreen,
     Change,
     Filter,
     SetFilter(Option<String>),
+    SetNameFilter(Option<NameFilter>),
     Tick,
+    Refresh,
+    // Sent by the resources lists once refreshed from the runtime
+    Refreshed,
     Render,
     Error(String),
+    Info(String),
     Resize(u16, u16),
     Resume,
     Suspend,
     CustomShell,
     SortColumn(u8),
     Help,
+    ErrorLog,
+    PauseToast,
     AutoScroll,
     Since(u16),
     LineWrap,
+    Ansi,
+    Timestamps,
+    JumpTo,
+    LevelFilter,
+    Visual,
+    Yank,
+    Export,
+    Changes,
+    Download,
+    Wait,
+    DetailsPane,
+    Stats,
+    Reveal,
+    CopyIp,
+    CopyPort,
+    Presets,
+    Unhealthy,
+    Stop,
+    Compare,
+    Palette,
+}
+
+impl Action {
+    // Actions changing the state of the runtime, rejected in read-only mode
+    pub(crate) fn is_mutating(&self) -> bool {
+        matches!(
+            self,
+            Action::Shell
+                | Action::CustomShell
+                | Action::Delete
+                | Action::Prune
+                | Action::Pull
+                | Action::Commit
+                | Action::Load
+                | Action::Stop
+        )
+    }
 }
 
 impl Display for Action {
@@ -1,21 +1,38 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
 use color_eyre::Result;
 use crossterm::event::{self, KeyCode, KeyEventKind, KeyModifiers};
+use eyre::eyre;
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Paragraph};
+use regex::Regex;
 use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio_util::sync::CancellationToken;
 
 use crate::action::Action;
 use crate::components::composes::Composes;
 use crate::components::containers::Containers;
+use crate::components::events::Events;
 use crate::components::images::Images;
+use crate::components::input::TextInput;
 use crate::components::networks::Networks;
 use crate::components::volumes::Volumes;
 use crate::components::Component;
+use crate::config;
 use crate::runtime::{
-    get_suggestions, RuntimeSummary, COMPOSES, CONTAINERS, IMAGES, NETWORKS, VOLUMES,
+    self, get_suggestions, ConnectionConfig, NameFilter, RuntimeSummary, COMPOSES, CONTAINERS,
+    EVENTS, IMAGES, NETWORKS, VOLUMES,
 };
 use crate::tui;
-use crate::utils::{default_layout, help_screen, toast};
+use crate::utils::{
+    connecting_screen, default_layout, error_log_screen, help_screen, is_too_small,
+    palette_bindings, palette_popup, parse_binding, presets_popup, runtime_picker_screen,
+    safe_subslice, toast, too_small_screen,
+};
 
 enum InputMode {
     None,
@@ -23,7 +40,19 @@ enum InputMode {
     Filter,
 }
 
-const DEFAULT_TOAST_DELAY: usize = 8;
+const ERROR_LOG_CAPACITY: usize = 50;
+
+const TOAST_SCROLL: usize = 5;
+
+const HELP_SCROLL: usize = 10;
+
+const FOOTER_BINDINGS: usize = 4;
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+// Delay between two frames of the spinner, it is only shown for the requests lasting longer
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+const RESOURCES_CYCLE: [&str; 5] = [CONTAINERS, IMAGES, VOLUMES, NETWORKS, COMPOSES];
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Popup {
@@ -32,38 +61,157 @@ pub enum Popup {
         msg: String,
         timeout: usize,
         ttl: usize,
+        scroll: usize,
+    },
+    Info {
+        msg: String,
+        timeout: usize,
+        ttl: usize,
+        scroll: usize,
+    },
+    Help {
+        search: TextInput,
+        scroll: usize,
+    },
+    ErrorLog,
+    Presets {
+        resource: &'static str,
+        selected: usize,
+    },
+    Palette {
+        search: TextInput,
+        selected: usize,
     },
-    Help,
 }
 
 pub struct App {
     should_quit: bool,
     should_suspend: bool,
-    input: String,
+    input: TextInput,
     input_mode: InputMode,
-    cursor_position: usize,
     suggestion: Option<&'static str>,
     version: &'static str,
     frame_rate: f64,
     tick_rate: f64,
     show_popup: Popup,
     runtime_info: Option<RuntimeSummary>,
+    resources: &'static [&'static str],
+    pending_key: Option<KeyCode>,
+    pending_requests: usize,
+    spinner: usize,
+    errors: VecDeque<(DateTime<Local>, String)>,
+    toast_paused: bool,
+    read_only: bool,
+    last_refresh: Option<Instant>,
+    // Detected runtimes, the user picks one when there are several
+    configs: Vec<ConnectionConfig>,
+    // Whether the connection was established before starting the UI
+    connected: bool,
 }
 
 impl App {
-    pub fn new(version: &'static str, tick_rate: f64, frame_rate: f64) -> Self {
+    pub fn new(
+        version: &'static str,
+        tick_rate: f64,
+        frame_rate: f64,
+        read_only: bool,
+        configs: Vec<ConnectionConfig>,
+        connected: bool,
+    ) -> Self {
         App {
             should_quit: false,
             should_suspend: false,
-            input: "".to_string(),
+            input: TextInput::default(),
             input_mode: InputMode::None,
             suggestion: None,
-            cursor_position: 0,
             version,
             frame_rate,
             tick_rate,
             show_popup: Popup::None,
             runtime_info: None,
+            resources: &[],
+            pending_key: None,
+            pending_requests: 0,
+            spinner: 0,
+            errors: VecDeque::with_capacity(ERROR_LOG_CAPACITY),
+            toast_paused: false,
+            read_only,
+            last_refresh: None,
+            configs,
+            connected,
+        }
+    }
+
+    // Lists the detected runtimes until one is chosen, returns None when the user quits
+    async fn pick_runtime(&mut self, tui: &mut tui::Tui) -> Result<Option<ConnectionConfig>> {
+        if self.configs.len() == 1 {
+            return Ok(self.configs.first().cloned());
+        }
+        let runtimes: Vec<String> = self
+            .configs
+            .iter()
+            .map(|c| format!("{} - {}", c.runtime_name(), c))
+            .collect();
+        let mut selected: usize = 0;
+        while let Some(event) = tui.next().await {
+            match event {
+                tui::Event::Key(kevent) if is_quit_key(&kevent) => return Ok(None),
+                tui::Event::Key(kevent) => match kevent.code {
+                    KeyCode::Up | KeyCode::Char('k') => selected = selected.saturating_sub(1),
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        selected = (selected + 1).min(runtimes.len() - 1)
+                    }
+                    KeyCode::Enter => return Ok(self.configs.get(selected).cloned()),
+                    _ => {}
+                },
+                tui::Event::Resize(w, h) => tui.resize(Rect::new(0, 0, w, h))?,
+                tui::Event::Render => {
+                    tui.draw(|f| runtime_picker_screen(f, &runtimes, selected))?;
+                }
+                _ => {}
+            }
+        }
+        Ok(None)
+    }
+
+    // Shows a connecting screen until the runtime answers, then an error screen if it failed.
+    // Returns None when the user quits before the connection is established.
+    async fn connect(
+        &mut self,
+        tui: &mut tui::Tui,
+        config: ConnectionConfig,
+    ) -> Result<Option<RuntimeSummary>> {
+        let target = config.to_string();
+        let connected = self.connected;
+        let mut connection = tokio::spawn(async move {
+            if !connected {
+                runtime::connect(config).await?;
+            }
+            runtime::get_runtime_info().await
+        });
+        let mut error: Option<String> = None;
+        loop {
+            tokio::select! {
+                result = &mut connection, if error.is_none() => match result? {
+                    Ok(info) => return Ok(Some(info)),
+                    Err(e) => error = Some(e.to_string()),
+                },
+                Some(event) = tui.next() => match event {
+                    tui::Event::Key(kevent) if is_quit_key(&kevent) => {
+                        return match error {
+                            Some(e) => Err(eyre!("Unable to connect to {}: {}", target, e)),
+                            None => Ok(None),
+                        };
+                    }
+                    tui::Event::Tick => self.spinner = (self.spinner + 1) % SPINNER.len(),
+                    tui::Event::Resize(w, h) => tui.resize(Rect::new(0, 0, w, h))?,
+                    tui::Event::Render => {
+                        let spinner = SPINNER[self.spinner];
+                        tui.draw(|f| connecting_screen(f, &target, spinner, error.as_deref()))?;
+                    }
+                    _ => {}
+                },
+            }
         }
     }
 
@@ -75,11 +223,41 @@ impl App {
         tui.frame_rate(self.frame_rate);
         tui.enter()?;
 
-        let mut main: Component = Component::Containers(Containers::new(Default::default()));
-        main.register_action_handler(action_tx.clone());
-
-        let info = crate::runtime::get_runtime_info().await?;
+        let Some(config) = self.pick_runtime(&mut tui).await? else {
+            tui.exit()?;
+            return Ok(());
+        };
+        if config.is_interactive() && !self.connected {
+            // The ssh prompts need the terminal, the UI is left meanwhile
+            tui.stop()?;
+            tui.exit()?;
+            runtime::connect(config.clone()).await?;
+            self.connected = true;
+            tui = tui::Tui::new()?;
+            tui.tick_rate(self.tick_rate);
+            tui.frame_rate(self.frame_rate);
+            tui.enter()?;
+        }
+        let connection = self.connect(&mut tui, config).await;
+        let info = match connection {
+            Ok(Some(info)) => info,
+            Ok(None) | Err(_) => {
+                tui.exit()?;
+                return connection.map(|_| ());
+            }
+        };
         self.runtime_info = Some(info);
+        self.resources = get_suggestions().await;
+        let cancel = CancellationToken::new();
+        // Stops the runtime info refresh whichever way the loop is left
+        let _refresh_guard = cancel.clone().drop_guard();
+        tokio::spawn(runtime::refresh_runtime_info(cancel));
+
+        let mut main: Component =
+            Component::Containers(Box::new(Containers::new(Default::default())));
+        // Screens left for the current one, restored as they were when going back
+        let mut screens: Vec<Component> = vec![];
+        main.register_action_handler(action_tx.clone());
 
         loop {
             if let Some(event) = tui.next().await {
@@ -87,6 +265,23 @@ impl App {
                     tui::Event::Tick => action_tx.send(Action::Tick)?,
                     tui::Event::Render => action_tx.send(Action::Render)?,
                     tui::Event::Resize(x, y) => action_tx.send(Action::Resize(x, y))?,
+                    tui::Event::Paste(text) => match self.input_mode {
+                        InputMode::Change | InputMode::Filter => {
+                            self.input.paste(&text);
+                            self.input_changed(&action_tx).await?;
+                        }
+                        InputMode::None => match &mut self.show_popup {
+                            Popup::Palette { search, selected } => {
+                                search.paste(&text);
+                                *selected = 0;
+                            }
+                            Popup::Help { search, scroll } => {
+                                search.paste(&text);
+                                *scroll = 0;
+                            }
+                            _ => main.handle_paste(&text),
+                        },
+                    },
                     tui::Event::Key(kevent) => match self.input_mode {
                         InputMode::Change | InputMode::Filter => {
                             self.handle_input(kevent, action_tx.clone()).await?;
@@ -118,7 +313,13 @@ impl App {
                         self.draw(&mut tui, &mut main)?;
                     }
                     Action::Tick => {
-                        if let Popup::Error { ttl, .. } = &mut self.show_popup {
+                        // Refreshed in the background by `refresh_runtime_info`
+                        if let Some(info) = crate::runtime::cached_runtime_info().await {
+                            self.runtime_info = Some(info);
+                        }
+                        if let (Popup::Error { ttl, .. } | Popup::Info { ttl, .. }, false) =
+                            (&mut self.show_popup, self.toast_paused)
+                        {
                             if *ttl > 0 {
                                 *ttl = ttl.saturating_sub(1);
                             } else {
@@ -126,10 +327,44 @@ impl App {
                             }
                         }
                     }
+                    Action::Refresh => {
+                        action_tx.send(Action::Tick)?;
+                    }
+                    Action::Refreshed => {
+                        self.last_refresh = Some(Instant::now());
+                    }
                     Action::Screen(ref screen) => {
                         let mut new_main = screen.clone();
                         new_main.register_action_handler(action_tx.clone());
                         new_main.setup(&mut tui)?;
+                        let mut previous = std::mem::replace(&mut main, new_main);
+                        // The resources lists are the roots of the navigation and an exec
+                        // can't be resumed, none of them is kept
+                        if resource_name(&main).is_some() {
+                            for mut screen in screens.drain(..) {
+                                screen.teardown(&mut tui)?;
+                            }
+                            previous.teardown(&mut tui)?;
+                        } else if let Component::ContainerExec(_) = previous {
+                            previous.teardown(&mut tui)?;
+                        } else {
+                            previous.pause()?;
+                            screens.push(previous);
+                        }
+                    }
+                    Action::Back(ref fallback) => {
+                        let mut new_main = match screens.pop() {
+                            Some(mut previous) => {
+                                previous.resume()?;
+                                previous
+                            }
+                            None => {
+                                let mut new_main = fallback.clone().build();
+                                new_main.register_action_handler(action_tx.clone());
+                                new_main
+                            }
+                        };
+                        new_main.setup(&mut tui)?;
                         main.teardown(&mut tui)?;
                         main = new_main;
                     }
@@ -140,30 +375,119 @@ impl App {
                         self.input_mode = InputMode::Filter;
                     }
                     Action::Help => {
-                        self.show_popup = Popup::Help;
+                        self.show_popup = Popup::Help {
+                            search: TextInput::default(),
+                            scroll: 0,
+                        };
+                    }
+                    Action::ErrorLog => {
+                        self.show_popup = Popup::ErrorLog;
+                    }
+                    Action::Palette => {
+                        self.show_popup = Popup::Palette {
+                            search: TextInput::default(),
+                            selected: 0,
+                        };
+                    }
+                    Action::Presets => {
+                        match resource_name(&main) {
+                            Some(resource) if !config::get().presets(resource).is_empty() => {
+                                self.show_popup = Popup::Presets {
+                                    resource,
+                                    selected: 0,
+                                };
+                            }
+                            _ => action_tx.send(Action::Info(
+                                "No filter preset is configured for this view".to_string(),
+                            ))?,
+                        }
+                        continue;
+                    }
+                    Action::PageUp | Action::PageDown => {
+                        if let Popup::Error { scroll, .. } | Popup::Info { scroll, .. } =
+                            &mut self.show_popup
+                        {
+                            *scroll = match action {
+                                Action::PageUp => scroll.saturating_sub(TOAST_SCROLL),
+                                _ => scroll.saturating_add(TOAST_SCROLL),
+                            };
+                            continue;
+                        }
+                    }
+                    Action::PauseToast => {
+                        if let Popup::Error { .. } | Popup::Info { .. } = self.show_popup {
+                            self.toast_paused = !self.toast_paused;
+                        }
                     }
                     Action::PreviousScreen => {
                         if let InputMode::Change = self.input_mode {
                             self.reset_input();
                         }
                         match self.show_popup {
-                            Popup::Error { .. } | Popup::Help => {
+                            Popup::Error { .. }
+                            | Popup::Info { .. }
+                            | Popup::Help { .. }
+                            | Popup::ErrorLog
+                            | Popup::Presets { .. }
+                            | Popup::Palette { .. } => {
                                 self.show_popup = Popup::None;
                             }
                             Popup::None => {}
                         }
                     }
                     Action::Error(ref msg) => {
+                        if self.errors.len() >= ERROR_LOG_CAPACITY {
+                            self.errors.pop_back();
+                        }
+                        self.errors.push_front((Local::now(), msg.to_string()));
                         self.show_popup = Popup::Error {
                             msg: msg.to_string(),
-                            timeout: DEFAULT_TOAST_DELAY,
-                            ttl: DEFAULT_TOAST_DELAY,
+                            timeout: self.toast_delay(),
+                            ttl: self.toast_delay(),
+                            scroll: 0,
+                        };
+                        self.toast_paused = false;
+                    }
+                    Action::Info(ref msg) => {
+                        self.show_popup = Popup::Info {
+                            msg: msg.to_string(),
+                            timeout: self.toast_delay(),
+                            ttl: self.toast_delay(),
+                            scroll: 0,
                         };
+                        self.toast_paused = false;
                     }
                     _ => {}
                 };
                 if let InputMode::None = self.input_mode {
-                    main.update(action.clone()).await?;
+                    // An exec session owns the terminal until it ends, nothing is drawn meanwhile
+                    let exec = matches!(main, Component::ContainerExec(_));
+                    let result = if is_runtime_request(&action) && !exec {
+                        self.pending_requests += 1;
+                        // The component is busy until the request completes, the spinner is
+                        // animated on a copy of it as it was before the request
+                        let mut previous = main.clone();
+                        let update = main.update(action.clone());
+                        tokio::pin!(update);
+                        let mut spinner = tokio::time::interval_at(
+                            tokio::time::Instant::now() + SPINNER_INTERVAL,
+                            SPINNER_INTERVAL,
+                        );
+                        let result = loop {
+                            tokio::select! {
+                                result = &mut update => break result,
+                                _ = spinner.tick() => {
+                                    self.spinner = (self.spinner + 1) % SPINNER.len();
+                                    self.draw(&mut tui, &mut previous)?;
+                                }
+                            }
+                        };
+                        self.pending_requests = self.pending_requests.saturating_sub(1);
+                        result
+                    } else {
+                        main.update(action.clone()).await
+                    };
+                    result?;
                 }
             }
             if self.should_suspend {
@@ -174,6 +498,7 @@ impl App {
                 tui.frame_rate(self.frame_rate);
                 tui.enter()?;
             } else if self.should_quit {
+                main.teardown(&mut tui)?;
                 tui.stop()?;
                 break;
             }
@@ -187,12 +512,17 @@ impl App {
         tui: &mut tui::Tui,
         main_component: &mut Component,
     ) -> Result<(), color_eyre::eyre::Error> {
-        let main_layout = default_layout(tui.get_frame().size());
+        let size = tui.get_frame().size();
+        if is_too_small(size) {
+            tui.draw(|f| too_small_screen(f, size))?;
+            return Ok(());
+        }
+        let main_layout = default_layout(size);
         tui.draw(|f| {
             self.draw_header(f, main_layout[0]);
             main_component.draw(f, main_layout[1]);
             self.draw_popup(f, main_component);
-            self.draw_status(f, main_layout[2]);
+            self.draw_status(f, main_layout[2], main_component);
         })?;
         Ok(())
     }
@@ -200,12 +530,29 @@ impl App {
     fn draw_header(&self, f: &mut ratatui::prelude::Frame<'_>, rect: ratatui::prelude::Rect) {
         match self.input_mode {
             InputMode::None => {
+                let loading = if self.pending_requests > 0 {
+                    format!(" {} Loading...", SPINNER[self.spinner])
+                } else {
+                    String::new()
+                };
+                let updated = self
+                    .last_refresh
+                    .map(|t| format!(" Updated {}s ago", t.elapsed().as_secs()))
+                    .unwrap_or_default();
                 let text = if let Some(info) = &self.runtime_info {
                     vec![
-                        Line::from(format!(
-                            "Welcome to Doggy - Using {}@{}",
-                            info.name, info.version
-                        )),
+                        Line::from(vec![
+                            Span::raw(format!(
+                                "Welcome to Doggy - Using {}@{}",
+                                info.name, info.version
+                            )),
+                            Span::styled(
+                                if self.read_only { " [read-only]" } else { "" },
+                                Style::default().red().bold(),
+                            ),
+                            Span::styled(loading, Style::default().yellow()),
+                            Span::styled(updated, Style::default().dark_gray()),
+                        ]),
                         Line::from(format!(
                             "Connected to: {}",
                             info.config
@@ -215,7 +562,10 @@ impl App {
                         )),
                     ]
                 } else {
-                    vec![Line::from("Welcome to Doggy")]
+                    vec![Line::from(vec![
+                        Span::raw("Welcome to Doggy"),
+                        Span::styled(loading, Style::default().yellow()),
+                    ])]
                 };
                 let p = Paragraph::new(text);
                 f.render_widget(p, rect)
@@ -223,11 +573,11 @@ impl App {
             InputMode::Change => {
                 let mut spans = vec![
                     Span::styled("> ", Style::default().bold()),
-                    Span::styled(self.input.to_string(), Style::default().gray()),
+                    Span::styled(self.input.value().to_string(), Style::default().gray()),
                 ];
                 if let Some(suggestion) = self.suggestion {
                     spans.push(Span::styled(
-                        suggestion[self.cursor_position..].to_string(),
+                        safe_subslice(suggestion, self.input.cursor(), usize::MAX).to_string(),
                         Style::default().dark_gray(),
                     ));
                 }
@@ -239,7 +589,7 @@ impl App {
             InputMode::Filter => {
                 let input = Paragraph::new(Line::from(vec![
                     Span::styled("/ ", Style::default().bold()),
-                    Span::styled(self.input.to_string(), Style::default().gray()),
+                    Span::styled(self.input.value().to_string(), Style::default().gray()),
                 ]))
                 .block(Block::default().borders(Borders::ALL).title("Input"));
                 f.render_widget(input, rect);
@@ -247,55 +597,35 @@ impl App {
         }
     }
 
-    fn draw_status(&self, f: &mut ratatui::prelude::Frame<'_>, rect: ratatui::prelude::Rect) {
-        let p = Paragraph::new(format!("Doggy version {}", self.version).dark_gray());
-        f.render_widget(p, rect)
-    }
-
-    fn delete_char(&mut self) {
-        let is_not_cursor_leftmost = self.cursor_position != 0;
-        if is_not_cursor_leftmost {
-            // Method "remove" is not used on the saved text for deleting the selected char.
-            // Reason: Using remove on String works on bytes instead of the chars.
-            // Using remove would require special care because of char boundaries.
-
-            let current_index = self.cursor_position;
-            let from_left_to_current_index = current_index - 1;
-
-            // Getting all characters before the selected character.
-            let before_char_to_delete = self.input.chars().take(from_left_to_current_index);
-            // Getting all characters after selected character.
-            let after_char_to_delete = self.input.chars().skip(current_index);
-
-            // Put all characters together except the selected one.
-            // By leaving the selected one out, it is forgotten and therefore deleted.
-            self.input = before_char_to_delete.chain(after_char_to_delete).collect();
-            self.move_cursor_left();
+    fn draw_status(
+        &self,
+        f: &mut ratatui::prelude::Frame<'_>,
+        rect: ratatui::prelude::Rect,
+        main_component: &Component,
+    ) {
+        let version = format!("Doggy version {}", self.version);
+        let available = (rect.width as usize).saturating_sub(version.len() + 1);
+        let bindings = main_component
+            .get_bindings()
+            .unwrap_or_default()
+            .iter()
+            .take(FOOTER_BINDINGS)
+            .chain([("?", "Help")].iter());
+        // Only the bindings fitting entirely in the width are displayed
+        let mut legend = vec![];
+        let mut width = 0;
+        for (key, desc) in bindings {
+            let len = key.chars().count() + desc.chars().count() + 4;
+            if width + len > available {
+                break;
+            }
+            width += len;
+            legend.push(Span::styled(format!("<{}>", key), Style::default().cyan()));
+            legend.push(Span::styled(format!(" {} ", desc), Style::default().gray()));
         }
-    }
-
-    fn enter_char(&mut self, new_char: char) {
-        self.input.insert(self.cursor_position, new_char);
-
-        self.move_cursor_right();
-    }
-
-    fn move_cursor_left(&mut self) {
-        let cursor_moved_left = self.cursor_position.saturating_sub(1);
-        self.cursor_position = self.clamp_cursor(cursor_moved_left);
-    }
-
-    fn move_cursor_right(&mut self) {
-        let cursor_moved_right = self.cursor_position.saturating_add(1);
-        self.cursor_position = self.clamp_cursor(cursor_moved_right);
-    }
-
-    fn clamp_cursor(&self, new_cursor_pos: usize) -> usize {
-        new_cursor_pos.clamp(0, self.input.len())
-    }
-
-    fn reset_cursor(&mut self) {
-        self.cursor_position = 0;
+        legend.push(Span::raw(" ".repeat(available - width + 1)));
+        legend.push(Span::styled(version, Style::default().dark_gray()));
+        f.render_widget(Paragraph::new(Line::from(legend)), rect)
     }
 
     async fn handle_input(
@@ -313,75 +643,57 @@ impl App {
                         action_tx.send(Action::Error("No resource found".to_string()))?;
                     }
                 },
-                KeyCode::Char(to_insert) => {
-                    self.enter_char(to_insert);
-                    self.suggestion = self.update_suggestion().await;
-                }
-                KeyCode::Backspace => {
-                    self.delete_char();
-                }
-                KeyCode::Left => {
-                    self.move_cursor_left();
-                }
-                KeyCode::Right => {
-                    self.move_cursor_right();
-                }
                 KeyCode::Esc => {
-                    self.input = "".to_string();
-                    self.input_mode = InputMode::None;
-                    self.reset_cursor();
+                    if let InputMode::Filter = self.input_mode {
+                        action_tx.send(Action::SetNameFilter(None))?;
+                    }
+                    self.reset_input();
+                }
+                _ => {
+                    let previous = self.input.value().to_string();
+                    if self.input.handle_key(&kevent) && self.input.value() != previous {
+                        self.input_changed(&action_tx).await?;
+                    }
                 }
-                _ => {}
             }
         };
         Ok(())
     }
 
+    async fn input_changed(&mut self, action_tx: &UnboundedSender<Action>) -> Result<()> {
+        self.suggestion = self.update_suggestion().await;
+        self.send_name_filter(action_tx)
+    }
+
     fn submit_input(&mut self) -> Option<Action> {
         if let InputMode::Change = self.input_mode {
-            match self.suggestion {
-                Some(CONTAINERS) => {
-                    self.reset_input();
-                    Some(Action::Screen(Component::Containers(Containers::new(
-                        Default::default(),
-                    ))))
-                }
-                Some(COMPOSES) => {
-                    self.reset_input();
-                    Some(Action::Screen(Component::Composes(Composes::new())))
-                }
-                Some(IMAGES) => {
-                    self.reset_input();
-                    Some(Action::Screen(Component::Images(Images::new())))
-                }
-                Some(VOLUMES) => {
-                    self.reset_input();
-                    Some(Action::Screen(Component::Volumes(Volumes::new(
-                        Default::default(),
-                    ))))
-                }
-                Some(NETWORKS) => {
-                    self.reset_input();
-                    Some(Action::Screen(Component::Networks(Networks::new(
-                        Default::default(),
-                    ))))
-                }
-                _ => None,
+            let screen = self.suggestion.and_then(resource_screen);
+            if screen.is_some() {
+                self.reset_input();
             }
+            screen.map(Action::Screen)
         } else {
-            let input = self.input.clone();
+            let input = self.input.value().to_string();
             self.reset_input();
-            if input.is_empty() {
-                Some(Action::SetFilter(None))
+            filter_action(&input)
+        }
+    }
+
+    fn send_name_filter(&self, action_tx: &UnboundedSender<Action>) -> Result<()> {
+        if let InputMode::Filter = self.input_mode {
+            let input = self.input.value();
+            let filter = if input.is_empty() || input.starts_with('~') || input.contains('=') {
+                None
             } else {
-                Some(Action::SetFilter(Some(input.clone())))
-            }
+                Some(NameFilter::Fuzzy(input.to_string()))
+            };
+            action_tx.send(Action::SetNameFilter(filter))?;
         }
+        Ok(())
     }
 
     fn reset_input(&mut self) {
-        self.input = "".to_string();
-        self.cursor_position = 0;
+        self.input.clear();
         self.input_mode = InputMode::None;
     }
 
@@ -389,23 +701,155 @@ impl App {
         get_suggestions()
             .await
             .iter()
-            .find(|searched| searched.starts_with(&self.input))
+            .find(|searched| searched.starts_with(self.input.value()))
+            .copied()
+    }
+
+    fn toast_delay(&self) -> usize {
+        ((config::get().ui.toast_delay * self.tick_rate).round() as usize).max(1)
+    }
+
+    fn cycle_screen(&self, main: &Component, forward: bool) -> Option<Action> {
+        let current = resource_name(main)?;
+        let resources: Vec<&str> = RESOURCES_CYCLE
+            .iter()
+            .filter(|r| self.resources.contains(r))
             .copied()
+            .collect();
+        let position = resources.iter().position(|r| *r == current)?;
+        let next = if forward {
+            (position + 1) % resources.len()
+        } else {
+            (position + resources.len() - 1) % resources.len()
+        };
+        resource_screen(resources[next]).map(Action::Screen)
+    }
+
+    fn goto_screen(&self, resource: &str) -> Option<Action> {
+        if self.resources.contains(&resource) {
+            resource_screen(resource).map(Action::Screen)
+        } else {
+            None
+        }
+    }
+
+    fn handle_pending_key(&self, pending: KeyCode, kevent: event::KeyEvent) -> Option<Action> {
+        match (pending, kevent.code) {
+            (KeyCode::Char('g'), KeyCode::Char('g')) => Some(Action::Top),
+            (KeyCode::Char('g'), KeyCode::Char('c')) => self.goto_screen(CONTAINERS),
+            (KeyCode::Char('g'), KeyCode::Char('i')) => self.goto_screen(IMAGES),
+            (KeyCode::Char('g'), KeyCode::Char('v')) => self.goto_screen(VOLUMES),
+            (KeyCode::Char('g'), KeyCode::Char('n')) => self.goto_screen(NETWORKS),
+            (KeyCode::Char('g'), KeyCode::Char('p')) => self.goto_screen(COMPOSES),
+            _ => None,
+        }
     }
 
     fn handle_key(
-        &self,
+        &mut self,
         main: &Component,
         kevent: event::KeyEvent,
         action_tx: UnboundedSender<Action>,
     ) -> Result<()> {
-        let action = if self.show_popup == Popup::None {
-            main.get_action(&kevent)
-        } else {
-            None
+        if let Popup::Help { search, scroll } = &mut self.show_popup {
+            match kevent.code {
+                KeyCode::Up => *scroll = scroll.saturating_sub(1),
+                KeyCode::Down => *scroll = scroll.saturating_add(1),
+                KeyCode::PageUp => *scroll = scroll.saturating_sub(HELP_SCROLL),
+                KeyCode::PageDown => *scroll = scroll.saturating_add(HELP_SCROLL),
+                KeyCode::Esc => action_tx.send(Action::PreviousScreen)?,
+                _ => {
+                    if search.handle_key(&kevent) {
+                        *scroll = 0;
+                    }
+                }
+            }
+            return Ok(());
+        }
+        if let Popup::Presets { resource, selected } = &mut self.show_popup {
+            let presets = config::get().presets(resource);
+            match kevent.code {
+                KeyCode::Up | KeyCode::Char('k') => *selected = selected.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    *selected = (*selected + 1).min(presets.len().saturating_sub(1))
+                }
+                KeyCode::Enter => {
+                    if let Some(action) = presets
+                        .get(*selected)
+                        .and_then(|(_, filter)| filter_action(filter))
+                    {
+                        action_tx.send(action)?;
+                    }
+                    self.show_popup = Popup::None;
+                }
+                KeyCode::Esc => self.show_popup = Popup::None,
+                _ => {}
+            }
+            return Ok(());
+        }
+        if let Popup::Palette { search, selected } = &mut self.show_popup {
+            let bindings = palette_bindings(main, search.value());
+            match kevent.code {
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down => *selected = (*selected + 1).min(bindings.len().saturating_sub(1)),
+                KeyCode::Enter => {
+                    // The chosen command is run as if its key was pressed
+                    let kevent = bindings.get(*selected).and_then(|(k, _)| parse_binding(k));
+                    self.show_popup = Popup::None;
+                    if let Some(kevent) = kevent {
+                        return self.handle_key(main, kevent, action_tx);
+                    }
+                }
+                KeyCode::Esc => self.show_popup = Popup::None,
+                _ => {
+                    if search.handle_key(&kevent) {
+                        *selected = 0;
+                    }
+                }
+            }
+            return Ok(());
+        }
+        if let Some(pending) = self.pending_key.take() {
+            if let Some(action) = self.handle_pending_key(pending, kevent) {
+                action_tx.send(action)?;
+            }
+            return Ok(());
+        }
+        let action =
+            if kevent.code == KeyCode::Char('p') && kevent.modifiers == KeyModifiers::CONTROL {
+                // Checked first as the components ignore the modifiers of their bindings
+                Some(Action::Palette)
+            } else if self.show_popup == Popup::None {
+                main.get_action(&kevent)
+            } else {
+                None
+            };
+        let action = match action {
+            Some(action) => Some(action),
+            None if kevent.code == KeyCode::Char('g') => {
+                self.pending_key = Some(kevent.code);
+                None
+            }
+            None => self.global_action(main, &kevent),
         };
-        let action = action.or(match kevent.code {
+        if let Some(action) = action {
+            if self.read_only && action.is_mutating() {
+                action_tx.send(Action::Info(format!(
+                    "{} is disabled in read-only mode",
+                    action
+                )))?;
+            } else {
+                action_tx.send(action)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn global_action(&self, main: &Component, kevent: &event::KeyEvent) -> Option<Action> {
+        match kevent.code {
             KeyCode::Char('a') => Some(Action::All),
+            KeyCode::Char('b') if main.has_filter() => Some(Action::Presets),
             KeyCode::Char('q') => Some(Action::Quit),
             KeyCode::Char(':') => Some(Action::Change),
             KeyCode::Char('/') => {
@@ -417,7 +861,17 @@ impl App {
             }
             KeyCode::Char('j') | KeyCode::Down => Some(Action::Down),
             KeyCode::Char('k') | KeyCode::Up => Some(Action::Up),
+            KeyCode::Char('G') => Some(Action::Bottom),
             KeyCode::Char('?') => Some(Action::Help),
+            KeyCode::Char('E') => Some(Action::ErrorLog),
+            KeyCode::Char('r') => Some(Action::Refresh),
+            KeyCode::Char(' ') => Some(Action::PauseToast),
+            KeyCode::Char(c @ '1'..='5') => {
+                let index = c as usize - '1' as usize;
+                self.goto_screen(RESOURCES_CYCLE[index])
+            }
+            KeyCode::Tab => self.cycle_screen(main, true),
+            KeyCode::BackTab => self.cycle_screen(main, false),
             KeyCode::F(n) => Some(Action::SortColumn(n)),
             KeyCode::PageUp => Some(Action::PageUp),
             KeyCode::PageDown => Some(Action::PageDown),
@@ -431,24 +885,132 @@ impl App {
                 }
             }
             _ => None,
-        });
-        if let Some(action) = action {
-            action_tx.send(action)?;
         }
+    }
 
-        Ok(())
+    // Whether the binding displayed in the help triggers an action rejected in read-only mode
+    fn is_disabled(&self, main: &Component, binding: &str) -> bool {
+        self.read_only
+            && parse_binding(binding)
+                .and_then(|k| main.get_action(&k).or_else(|| self.global_action(main, &k)))
+                .is_some_and(|a| a.is_mutating())
     }
 
     fn draw_popup(&mut self, f: &mut Frame<'_>, main_component: &Component) {
         match &mut self.show_popup {
-            Popup::Error { msg, timeout, ttl } => {
+            Popup::Error {
+                msg,
+                timeout,
+                ttl,
+                scroll,
+            } => {
                 let title = Span::styled("Error", Style::new().red());
-                toast(f, title, msg, *timeout, *ttl);
+                toast(f, title, msg, *timeout, *ttl, self.toast_paused, scroll);
+            }
+            Popup::Info {
+                msg,
+                timeout,
+                ttl,
+                scroll,
+            } => {
+                let title = Span::styled("Info", Style::new().green());
+                toast(f, title, msg, *timeout, *ttl, self.toast_paused, scroll);
             }
-            Popup::Help => {
-                help_screen(f, main_component);
+            Popup::Help { search, scroll } => {
+                let (search, mut scroll) = (search.value().to_string(), *scroll);
+                help_screen(f, main_component, &search, &mut scroll, |b| {
+                    self.is_disabled(main_component, b)
+                });
+                if let Popup::Help { scroll: s, .. } = &mut self.show_popup {
+                    *s = scroll;
+                }
+            }
+            Popup::ErrorLog => {
+                error_log_screen(f, &self.errors);
+            }
+            Popup::Presets { resource, selected } => {
+                let presets: Vec<String> = config::get()
+                    .presets(resource)
+                    .iter()
+                    .map(|(name, filter)| format!("{} ({})", name, filter))
+                    .collect();
+                presets_popup(f, &presets, *selected);
+            }
+            Popup::Palette { search, selected } => {
+                let (search, selected) = (search.value().to_string(), *selected);
+                palette_popup(
+                    f,
+                    &palette_bindings(main_component, &search),
+                    &search,
+                    selected,
+                    |b| self.is_disabled(main_component, b),
+                );
             }
             Popup::None => {}
         }
     }
 }
+
+// Filter typed after `/`: `key=value` filters are handled by the runtime, `~` prefixes a regex
+fn filter_action(input: &str) -> Option<Action> {
+    if input.is_empty() {
+        Some(Action::SetFilter(None))
+    } else if let Some(pattern) = input.strip_prefix('~') {
+        match Regex::new(pattern) {
+            Ok(regex) => Some(Action::SetNameFilter(Some(NameFilter::Regex(regex)))),
+            Err(e) => Some(Action::Error(format!("Invalid regex: {}", e))),
+        }
+    } else if input.contains('=') {
+        Some(Action::SetFilter(Some(input.to_string())))
+    } else {
+        Some(Action::SetNameFilter(Some(NameFilter::Fuzzy(
+            input.to_string(),
+        ))))
+    }
+}
+
+fn is_quit_key(kevent: &event::KeyEvent) -> bool {
+    match kevent.code {
+        KeyCode::Char('q') | KeyCode::Esc => true,
+        KeyCode::Char('c') => kevent.modifiers == KeyModifiers::CONTROL,
+        _ => false,
+    }
+}
+
+fn is_runtime_request(action: &Action) -> bool {
+    matches!(
+        action,
+        Action::Tick
+            | Action::Inspect
+            | Action::Logs
+            | Action::Ok
+            | Action::Changes
+            | Action::Download
+            | Action::SetFilter(_)
+    )
+}
+
+fn resource_name(component: &Component) -> Option<&'static str> {
+    match component {
+        Component::Containers(_) => Some(CONTAINERS),
+        Component::Composes(_) => Some(COMPOSES),
+        Component::Images(_) => Some(IMAGES),
+        Component::Networks(_) => Some(NETWORKS),
+        Component::Volumes(_) => Some(VOLUMES),
+        _ => None,
+    }
+}
+
+fn resource_screen(resource: &str) -> Option<Component> {
+    match resource {
+        CONTAINERS => Some(Component::Containers(Box::new(Containers::new(
+            Default::default(),
+        )))),
+        COMPOSES => Some(Component::Composes(Composes::new())),
+        IMAGES => Some(Component::Images(Images::new())),
+        VOLUMES => Some(Component::Volumes(Volumes::new(Default::default()))),
+        NETWORKS => Some(Component::Networks(Networks::new(Default::default()))),
+        EVENTS => Some(Component::Events(Events::new())),
+        _ => None,
+    }
+}
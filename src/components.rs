@@ -8,13 +8,17 @@ use crate::action::Action;
 
 use crate::components::compose_view::ComposeView;
 use crate::components::composes::Composes;
+use crate::components::container_diff::ContainerDiff;
 use crate::components::container_exec::ContainerExec;
 use crate::components::container_inspect::ContainerDetails;
 use crate::components::container_logs::ContainerLogs;
+use crate::components::container_stats::ContainerStats;
 use crate::components::container_view::ContainerView;
 use crate::components::containers::Containers;
+use crate::components::events::Events;
 use crate::components::image_inspect::ImageInspect;
 use crate::components::images::Images;
+use crate::components::inspect_diff::InspectDiff;
 use crate::components::network_inspect::NetworkInspect;
 use crate::components::networks::Networks;
 use crate::components::volume_inspect::VolumeInspect;
@@ -23,13 +27,18 @@ use crate::tui;
 
 pub mod compose_view;
 pub mod composes;
+pub mod container_diff;
 pub mod container_exec;
 pub mod container_inspect;
 pub mod container_logs;
+pub mod container_stats;
 pub mod container_view;
 pub mod containers;
+pub mod events;
 pub mod image_inspect;
 pub mod images;
+pub mod input;
+pub mod inspect_diff;
 pub mod network_inspect;
 pub mod networks;
 pub mod volume_inspect;
@@ -37,21 +46,53 @@ pub mod volumes;
 
 #[derive(Clone, Debug)]
 pub(crate) enum Component {
-    Containers(Containers),
+    Containers(Box<Containers>),
+    ContainerDiff(ContainerDiff),
     ContainerExec(ContainerExec),
     ContainerInspect(ContainerDetails),
     ContainerLogs(ContainerLogs),
-    ContainerView(ContainerView),
+    ContainerStats(ContainerStats),
+    ContainerView(Box<ContainerView>),
     Composes(Composes),
     ComposeView(ComposeView),
+    Events(Events),
     Images(Images),
     ImageInspect(ImageInspect),
+    InspectDiff(InspectDiff),
     Networks(Networks),
     NetworkInspect(NetworkInspect),
     Volumes(Volumes),
     VolumeInspect(VolumeInspect),
 }
 
+// The screen to go back to when none was left for the current one, it is only built when used
+#[derive(Clone, Debug)]
+pub(crate) enum Fallback {
+    Containers,
+    ContainerView(String),
+    Composes,
+    Images,
+    Networks,
+    Volumes,
+}
+
+impl Fallback {
+    pub(crate) fn build(self) -> Component {
+        match self {
+            Fallback::Containers => {
+                Component::Containers(Box::new(Containers::new(Default::default())))
+            }
+            Fallback::ContainerView(cid) => {
+                Component::ContainerView(Box::new(ContainerView::new(cid)))
+            }
+            Fallback::Composes => Component::Composes(Composes::new()),
+            Fallback::Images => Component::Images(Images::new()),
+            Fallback::Networks => Component::Networks(Networks::new(Default::default())),
+            Fallback::Volumes => Component::Volumes(Volumes::new(Default::default())),
+        }
+    }
+}
+
 macro_rules! component_delegate {
     ($self:ident.$func:ident$args:tt, [$($member:tt),+]) => {
         match $self {
@@ -83,14 +124,18 @@ impl Component {
             self.get_name(),
             [
                 Containers,
+                ContainerDiff,
                 ContainerExec,
                 ContainerInspect,
                 ContainerLogs,
+                ContainerStats,
                 ContainerView,
                 Composes,
                 ComposeView,
+                Events,
                 Images,
                 ImageInspect,
+                InspectDiff,
                 Networks,
                 NetworkInspect,
                 Volumes,
@@ -104,14 +149,18 @@ impl Component {
             self.register_action_handler(action_tx),
             [
                 Containers,
+                ContainerDiff,
                 ContainerExec,
                 ContainerInspect,
                 ContainerLogs,
+                ContainerStats,
                 ContainerView,
                 Composes,
                 ComposeView,
+                Events,
                 Images,
                 ImageInspect,
+                InspectDiff,
                 Networks,
                 NetworkInspect,
                 Volumes,
@@ -125,14 +174,18 @@ impl Component {
             self.update(action).await,
             [
                 Containers,
+                ContainerDiff,
                 ContainerExec,
                 ContainerInspect,
                 ContainerLogs,
+                ContainerStats,
                 ContainerView,
                 Composes,
                 ComposeView,
+                Events,
                 Images,
                 ImageInspect,
+                InspectDiff,
                 Networks,
                 NetworkInspect,
                 Volumes,
@@ -156,13 +209,17 @@ impl Component {
             self.draw(f, area),
             [
                 Containers,
+                ContainerDiff,
                 ContainerInspect,
                 ContainerLogs,
+                ContainerStats,
                 ContainerView,
                 Composes,
                 ComposeView,
+                Events,
                 Images,
                 ImageInspect,
+                InspectDiff,
                 Networks,
                 NetworkInspect,
                 Volumes,
@@ -176,14 +233,67 @@ impl Component {
         component_delegate!(self.setup(t), [ContainerExec], Ok(()))
     }
     pub(crate) fn teardown(&mut self, t: &mut tui::Tui) -> Result<()> {
-        component_delegate!(self.teardown(t), [ContainerExec, Containers], Ok(()))
+        component_delegate!(
+            self.teardown(t),
+            [
+                ContainerExec,
+                ContainerLogs,
+                ContainerStats,
+                ContainerView,
+                Containers,
+                Events
+            ],
+            Ok(())
+        )
+    }
+
+    // Stops the background tasks of a screen kept in the navigation stack
+    pub(crate) fn pause(&mut self) -> Result<()> {
+        component_delegate!(
+            self.pause(),
+            [
+                ContainerLogs,
+                ContainerStats,
+                ContainerView,
+                Containers,
+                Events
+            ],
+            Ok(())
+        )
+    }
+
+    // Starts again the background tasks of a screen restored from the navigation stack
+    pub(crate) fn resume(&mut self) -> Result<()> {
+        component_delegate!(
+            self.resume(),
+            [
+                ContainerLogs,
+                ContainerStats,
+                ContainerView,
+                Containers,
+                Events
+            ],
+            Ok(())
+        )
     }
 
     pub(crate) fn handle_input(
         &mut self,
         kevent: event::KeyEvent,
     ) -> Result<Option<event::KeyEvent>> {
-        component_delegate!(self.handle_input(kevent), [Containers], Ok(Some(kevent)))
+        component_delegate!(
+            self.handle_input(kevent),
+            [ContainerLogs, Containers, ContainerView, Images],
+            Ok(Some(kevent))
+        )
+    }
+
+    pub(crate) fn handle_paste(&mut self, text: &str) {
+        component_delegate!(
+            self.handle_paste(text),
+            [ContainerLogs, Containers, ContainerView, Images],
+            {}
+        )
     }
 
     pub(crate) fn get_bindings(&self) -> Option<&[(&str, &str)]> {
@@ -194,6 +304,8 @@ impl Component {
                 ContainerLogs,
                 ContainerView,
                 Composes,
+                ComposeView,
+                Events,
                 Images,
                 Networks,
                 Volumes
@@ -210,6 +322,8 @@ impl Component {
                 ContainerLogs,
                 ContainerView,
                 Composes,
+                ComposeView,
+                Events,
                 Images,
                 Networks,
                 Volumes
@@ -221,7 +335,14 @@ impl Component {
     pub(crate) fn has_filter(&self) -> bool {
         component_delegate!(
             self.has_filter(),
-            [Containers, Images, Networks, Volumes],
+            [
+                ContainerLogs,
+                ContainerView,
+                Containers,
+                Images,
+                Networks,
+                Volumes
+            ],
             false
         )
     }
@@ -1,5 +1,9 @@
+use std::collections::HashSet;
+
 use color_eyre::Result;
 
+use crossterm::event::{self, KeyCode};
+use futures::future::join_all;
 use ratatui::{
     style::{Modifier, Style},
     text::{Line, Span, Text},
@@ -7,25 +11,37 @@ use ratatui::{
 };
 use tokio::sync::mpsc::UnboundedSender;
 
-use crate::{action::Action, runtime::Compose};
+use crate::{
+    action::Action,
+    runtime::{get_container_details, Compose},
+};
 
-use super::{composes::Composes, Component};
+use super::{
+    container_view::{ContainerView, DETAILS_REFRESH_TICKS},
+    Component, Fallback,
+};
 
 #[derive(Clone, Debug)]
 pub struct ComposeView {
     compose: Compose,
+    expanded: HashSet<(String, String)>,
+    selected: usize,
     action_tx: Option<UnboundedSender<Action>>,
     vertical_scroll_state: ScrollbarState,
     vertical_scroll: usize,
+    ticks: usize,
 }
 
 impl ComposeView {
     pub fn new(compose: Compose) -> Self {
         ComposeView {
             compose,
+            expanded: HashSet::new(),
+            selected: 0,
             action_tx: None,
             vertical_scroll_state: Default::default(),
             vertical_scroll: 0,
+            ticks: 0,
         }
     }
 
@@ -47,17 +63,90 @@ impl ComposeView {
         self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
     }
 
+    fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    fn next(&mut self) {
+        if self.selected + 1 < self.compose.services.len() {
+            self.selected += 1;
+        }
+    }
+
+    async fn toggle(&mut self) -> Result<()> {
+        if let Some(key) = self.compose.service_keys().get(self.selected) {
+            let key = (*key).clone();
+            if !self.expanded.remove(&key) {
+                self.expanded.insert(key.clone());
+                self.load_details(vec![key]).await?;
+            }
+        }
+        Ok(())
+    }
+
+    // Only the expanded services are inspected, concurrently
+    async fn load_details(&mut self, keys: Vec<(String, String)>) -> Result<()> {
+        let services: Vec<((String, String), String)> = keys
+            .into_iter()
+            .filter_map(|key| {
+                let id = self.compose.services.get(&key)?.id.clone();
+                Some((key, id))
+            })
+            .collect();
+        let results = join_all(services.iter().map(|(_, id)| get_container_details(id))).await;
+        for ((key, _), result) in services.into_iter().zip(results) {
+            match result {
+                Ok(details) => {
+                    self.compose.details.insert(key, details);
+                }
+                Err(e) => {
+                    self.expanded.remove(&key);
+                    let tx = self.action_tx.clone().expect("No action sender");
+                    tx.send(Action::Error(format!(
+                        "Unable to get service \"{}\" details:\n{}",
+                        key.0, e
+                    )))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub(crate) async fn update(&mut self, action: Action) -> Result<()> {
         let tx = self.action_tx.clone().expect("No action sender");
         match action {
             Action::PreviousScreen => {
-                tx.send(Action::Screen(Component::Composes(Composes::new())))?;
+                tx.send(Action::Back(Fallback::Composes))?;
             }
             Action::Up => {
-                self.up(1);
+                self.previous();
             }
             Action::Down => {
-                self.down(1);
+                self.next();
+            }
+            Action::Select => {
+                self.toggle().await?;
+            }
+            Action::Ok => {
+                // Built here and not in `get_action`, the view starts streaming its stats
+                let selected = self
+                    .compose
+                    .service_keys()
+                    .get(self.selected)
+                    .and_then(|key| self.compose.services.get(key));
+                if let Some(c) = selected {
+                    tx.send(Action::Screen(Component::ContainerView(Box::new(
+                        ContainerView::new(c.id.clone()),
+                    ))))?;
+                }
+            }
+            Action::Tick => {
+                self.ticks += 1;
+                if self.ticks >= DETAILS_REFRESH_TICKS {
+                    self.ticks = 0;
+                    self.load_details(self.expanded.iter().cloned().collect())
+                        .await?;
+                }
             }
             Action::PageUp => {
                 self.up(15);
@@ -70,16 +159,39 @@ impl ComposeView {
         Ok(())
     }
 
+    pub(crate) fn get_bindings(&self) -> Option<&[(&str, &str)]> {
+        Some(&[
+            ("Space", "Expand/collapse service"),
+            ("Enter", "Service container details"),
+        ])
+    }
+
+    pub(crate) fn get_action(&self, k: &event::KeyEvent) -> Option<Action> {
+        match k.code {
+            KeyCode::Char(' ') => Some(Action::Select),
+            KeyCode::Enter => Some(Action::Ok),
+            _ => None,
+        }
+    }
+
     pub(crate) fn draw(
         &mut self,
         f: &mut ratatui::prelude::Frame<'_>,
         area: ratatui::prelude::Rect,
     ) {
-        let text: Vec<Line> = (&self.compose).into();
+        let (text, selected_line): (Vec<Line>, usize) =
+            self.compose.tree_lines(&self.expanded, Some(self.selected));
+        // Keep the selected service within the bordered area
+        let height = area.height.saturating_sub(2) as usize;
+        if selected_line < self.vertical_scroll {
+            self.up(self.vertical_scroll - selected_line);
+        } else if height > 0 && selected_line >= self.vertical_scroll + height {
+            self.down(selected_line + 1 - self.vertical_scroll - height);
+        }
         let details = Paragraph::new(Text::from(text)).block(
             Block::default().borders(Borders::ALL).title(Span::styled(
                 format!(
-                    "Inspecting compose project: \"{}\" (press 'ESC' to previous screen, 'q' to quit)",
+                    "Inspecting compose project: \"{}\" (press 'Space' to expand/collapse a service, 'Enter' to view its container, 'ESC' to previous screen, 'q' to quit)",
                     self.compose.project
                 ),
                 Style::default().add_modifier(Modifier::BOLD),
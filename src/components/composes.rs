@@ -3,16 +3,18 @@ use crossterm::event::{self, KeyCode};
 use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::{Style, Stylize},
+    text::Line,
     widgets::TableState,
     Frame,
 };
 
-use tokio::sync::mpsc::UnboundedSender;
+use futures::future::join_all;
+use tokio::{spawn, sync::mpsc::UnboundedSender};
 
 use crate::{
     action::Action,
-    runtime::{list_compose_projects, Compose, Filter},
-    utils::table,
+    runtime::{list_compose_projects, stop_container, Compose, ContainerStatus, Filter},
+    utils::{confirmation_popup, empty_state, request_confirmation, table, table_page_size},
 };
 
 use super::{
@@ -27,11 +29,48 @@ const COMPOSES_CONSTRAINTS: [Constraint; 4] = [
     Constraint::Max(12),
 ];
 
+// Each stop can wait for the grace period of its container, they are run concurrently
+async fn stop(project: String, ids: Vec<String>, tx: UnboundedSender<Action>) -> Result<()> {
+    let results = join_all(ids.iter().map(|id| stop_container(id))).await;
+    let errors: Vec<String> = ids
+        .iter()
+        .zip(results)
+        .filter_map(|(id, r)| r.err().map(|e| format!("{}: {}", id, e)))
+        .collect();
+    let action = if errors.is_empty() {
+        Action::Info(format!(
+            "Stopped {} containers of project \"{}\"",
+            ids.len(),
+            project
+        ))
+    } else {
+        Action::Error(format!(
+            "Unable to stop {} of {} containers of project \"{}\":\n{}",
+            errors.len(),
+            ids.len(),
+            project,
+            errors.join("\n")
+        ))
+    };
+    tx.send(action)?;
+    tx.send(Action::Tick)?;
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+enum Popup {
+    None,
+    // Project and ids of its running containers
+    Stop(String, Vec<String>),
+}
+
 #[derive(Clone, Debug)]
 pub struct Composes {
     composes: Vec<Compose>,
     action_tx: Option<UnboundedSender<Action>>,
     state: TableState,
+    page_size: usize,
+    show_popup: Popup,
 }
 
 impl Composes {
@@ -40,6 +79,8 @@ impl Composes {
             composes: Vec::new(),
             action_tx: None,
             state: TableState::default(),
+            page_size: 1,
+            show_popup: Popup::None,
         }
     }
 
@@ -75,6 +116,36 @@ impl Composes {
         }
     }
 
+    fn first(&mut self) {
+        if !self.composes.is_empty() {
+            self.state.select(Some(0));
+        }
+    }
+
+    fn last(&mut self) {
+        if !self.composes.is_empty() {
+            self.state.select(Some(self.composes.len() - 1));
+        }
+    }
+
+    fn page_up(&mut self, qty: usize) {
+        if !self.composes.is_empty() {
+            let i = self.state.selected().unwrap_or(0).saturating_sub(qty);
+            self.state.select(Some(i));
+        }
+    }
+
+    fn page_down(&mut self, qty: usize) {
+        if !self.composes.is_empty() {
+            let i = self
+                .state
+                .selected()
+                .map_or(0, |i| i.saturating_add(qty))
+                .min(self.composes.len() - 1);
+            self.state.select(Some(i));
+        }
+    }
+
     fn get_selected_compose_info(&self) -> Option<Compose> {
         self.state
             .selected()
@@ -97,7 +168,10 @@ impl Composes {
         match action {
             Action::Tick => {
                 self.composes = match list_compose_projects().await {
-                    Ok(composes) => composes,
+                    Ok(composes) => {
+                        tx.send(Action::Refreshed)?;
+                        composes
+                    }
                     Err(e) => {
                         tx.send(Action::Error(format!(
                             "Error getting container list: {}",
@@ -117,7 +191,52 @@ impl Composes {
             Action::Up => {
                 self.previous();
             }
-            Action::Ok => {}
+            Action::Top => {
+                self.first();
+            }
+            Action::Bottom => {
+                self.last();
+            }
+            Action::PageUp => {
+                self.page_up(self.page_size);
+            }
+            Action::PageDown => {
+                self.page_down(self.page_size);
+            }
+            Action::Stop => {
+                if let Some(compose) = self.get_selected_compose_info() {
+                    let running: Vec<String> = compose
+                        .services
+                        .values()
+                        .filter(|c| matches!(c.status, ContainerStatus::Running(_)))
+                        .map(|c| c.id.to_string())
+                        .collect();
+                    if running.is_empty() {
+                        tx.send(Action::Info(format!(
+                            "No running container in project \"{}\"",
+                            compose.project
+                        )))?;
+                    } else {
+                        self.show_popup = Popup::Stop(compose.project, running);
+                        request_confirmation(&tx)?;
+                    }
+                }
+            }
+            Action::Ok => {
+                if let Popup::Stop(project, ids) =
+                    std::mem::replace(&mut self.show_popup, Popup::None)
+                {
+                    tx.send(Action::Info(format!(
+                        "Stopping {} containers of project \"{}\"...",
+                        ids.len(),
+                        project
+                    )))?;
+                    spawn(stop(project, ids, tx.clone()));
+                }
+            }
+            Action::PreviousScreen => {
+                self.show_popup = Popup::None;
+            }
             _ => {}
         }
         Ok(())
@@ -129,12 +248,27 @@ impl Composes {
             .split(area);
         let t = table(
             self.get_name().to_string(),
-            ["Project", "Containers", "Volumes", "Networks"],
+            &["Project", "Containers", "Volumes", "Networks"],
             self.composes.iter().map(|c| c.into()).collect(),
             &COMPOSES_CONSTRAINTS,
             Some(Style::new().gray()),
+            None,
         );
+        self.page_size = table_page_size(rects[0]);
         f.render_stateful_widget(t, rects[0], &mut self.state);
+        if self.composes.is_empty() {
+            empty_state(f, rects[0], "compose projects", &[]);
+        }
+        if let Popup::Stop(project, ids) = &self.show_popup {
+            confirmation_popup(
+                f,
+                Line::from(format!(
+                    "Are you sure you want to stop the {} running containers of project \"{}\"?",
+                    ids.len(),
+                    project
+                )),
+            );
+        }
     }
 
     pub(crate) fn get_bindings(&self) -> Option<&[(&str, &str)]> {
@@ -143,18 +277,23 @@ impl Composes {
             ("c", "Containers"),
             ("v", "Volumes"),
             ("n", "Networks"),
+            ("s", "Stop the running containers"),
         ])
     }
 
     pub(crate) fn get_action(&self, k: &event::KeyEvent) -> Option<Action> {
+        // Enter and ESC answer the confirmation
+        if !matches!(self.show_popup, Popup::None) {
+            return None;
+        }
         if let Some(compose) = self.get_selected_compose_info() {
             let filter = Filter::default().compose_project(compose.project.clone());
             match k.code {
                 KeyCode::Enter => Some(Action::Screen(Component::ComposeView(ComposeView::new(
                     compose,
                 )))),
-                KeyCode::Char('c') => Some(Action::Screen(Component::Containers(Containers::new(
-                    filter,
+                KeyCode::Char('c') => Some(Action::Screen(Component::Containers(Box::new(
+                    Containers::new(filter),
                 )))),
                 KeyCode::Char('v') => {
                     Some(Action::Screen(Component::Volumes(Volumes::new(filter))))
@@ -162,6 +301,7 @@ impl Composes {
                 KeyCode::Char('n') => {
                     Some(Action::Screen(Component::Networks(Networks::new(filter))))
                 }
+                KeyCode::Char('s') => Some(Action::Stop),
                 _ => None,
             }
         } else {
@@ -0,0 +1,103 @@
+use color_eyre::Result;
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph, ScrollbarState},
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::action::Action;
+use crate::components::Fallback;
+use crate::runtime::FileChange;
+use crate::utils::short_id;
+
+#[derive(Clone, Debug)]
+pub struct ContainerDiff {
+    cid: String,
+    name: String,
+    changes: Vec<FileChange>,
+    vertical_scroll_state: ScrollbarState,
+    vertical_scroll: usize,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl ContainerDiff {
+    pub fn new(cid: String, name: String, changes: Vec<FileChange>) -> Self {
+        ContainerDiff {
+            cid,
+            name,
+            changes,
+            vertical_scroll_state: Default::default(),
+            vertical_scroll: 0,
+            action_tx: None,
+        }
+    }
+
+    fn down(&mut self, qty: usize) {
+        self.vertical_scroll = self.vertical_scroll.saturating_add(qty);
+        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+    }
+
+    fn up(&mut self, qty: usize) {
+        self.vertical_scroll = self.vertical_scroll.saturating_sub(qty);
+        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+    }
+
+    pub(crate) fn get_name(&self) -> &'static str {
+        "ContainerDiff"
+    }
+
+    pub(crate) fn register_action_handler(&mut self, tx: UnboundedSender<Action>) {
+        self.action_tx = Some(tx);
+    }
+
+    pub(crate) async fn update(&mut self, action: Action) -> Result<()> {
+        match action {
+            Action::PreviousScreen => {
+                if let Some(tx) = self.action_tx.clone() {
+                    tx.send(Action::Back(Fallback::ContainerView(self.cid.to_string())))?;
+                }
+            }
+            Action::Up => {
+                self.up(1);
+            }
+            Action::Down => {
+                self.down(1);
+            }
+            Action::PageUp => {
+                self.up(15);
+            }
+            Action::PageDown => {
+                self.down(15);
+            }
+            _ => {}
+        };
+        Ok(())
+    }
+
+    pub(crate) fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let text: Vec<Line> = if self.changes.is_empty() {
+            vec![Line::from("No filesystem changes")]
+        } else {
+            self.changes.iter().map(|c| c.into()).collect()
+        };
+        let changes = Paragraph::new(text)
+            .gray()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .gray()
+                    .title(Span::styled(
+                        format!(
+                            "Filesystem changes for container: \"{}/{}\" (press 'ESC' to previous screen, 'q' to quit)",
+                            short_id(&self.cid),
+                            self.name
+                        ),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    )),
+            )
+            .scroll((self.vertical_scroll as u16, 0));
+
+        f.render_widget(changes, area);
+    }
+}
@@ -3,35 +3,59 @@ use color_eyre::Result;
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::action::Action;
-use crate::components::{containers::Containers, Component};
-use crate::runtime::container_exec;
+use crate::components::Fallback;
+use crate::config;
+use crate::runtime::{container_exec, ExecOptions};
 use crate::tui;
 
-const DEFAULT_CMD: &str = "/bin/bash";
+const FALLBACK_CMD: &str = "/bin/sh";
 
 #[derive(Clone, Debug)]
 pub struct ContainerExec {
     cid: String,
     cname: String,
-    command: String,
+    options: ExecOptions,
+    fallback: bool,
     action_tx: Option<UnboundedSender<Action>>,
     should_stop: bool,
 }
 
 impl ContainerExec {
-    pub fn new(cid: String, cname: String, command: Option<String>) -> Self {
-        log::debug!("{}>{:?}", cid, command);
+    pub fn new(cid: String, cname: String, options: Option<ExecOptions>) -> Self {
+        log::debug!("{}>{:?}", cid, options);
+        let mut options = options
+            .unwrap_or_default()
+            .with_env(config::get().exec.env());
+        let default_cmd = &config::get().exec.default_cmd;
+        let fallback = options.cmd.is_empty() && default_cmd != FALLBACK_CMD;
+        if options.cmd.is_empty() {
+            options.cmd = default_cmd.to_string();
+        }
         ContainerExec {
             cid,
             cname,
-            command: command.unwrap_or(DEFAULT_CMD.to_string()),
+            options,
+            fallback,
             action_tx: None,
             should_stop: false,
         }
     }
 
     async fn exec(&mut self) -> Result<()> {
-        container_exec(&self.cid, &self.command).await?;
+        let result = container_exec(&self.cid, &self.options).await?;
+
+        // 126 and 127 are returned when the command can't be found or executed, they are only
+        // considered when the process never ran: a session may also end with these codes
+        if self.fallback && !result.started && matches!(result.exit_code, Some(126) | Some(127)) {
+            let cmd = std::mem::replace(&mut self.options.cmd, FALLBACK_CMD.to_string());
+            container_exec(&self.cid, &self.options).await?;
+            if let Some(tx) = &self.action_tx {
+                tx.send(Action::Info(format!(
+                    "\"{}\" is not available in container \"{}\", used \"{}\" instead",
+                    cmd, self.cname, FALLBACK_CMD
+                )))?;
+            }
+        }
 
         Ok(())
     }
@@ -62,13 +86,11 @@ impl ContainerExec {
 
             self.should_stop = true;
             tx.send(Action::Resume)?;
-            tx.send(Action::Screen(Component::Containers(Containers::new(
-                Default::default(),
-            ))))?;
+            tx.send(Action::Back(Fallback::Containers))?;
             if let Err(e) = res {
                 tx.send(Action::Error(format!(
                     "Unable to execute command \"{}\" in container \"{}\"\n{}",
-                    self.command, self.cname, e
+                    self.options.cmd, self.cname, e
                 )))?;
             }
         }
@@ -8,7 +8,8 @@ use tokio::sync::mpsc::UnboundedSender;
 
 use crate::action::Action;
 
-use crate::components::{containers::Containers, Component};
+use crate::components::Fallback;
+use crate::utils::short_id;
 
 #[derive(Clone, Debug)]
 pub struct ContainerDetails {
@@ -54,9 +55,7 @@ impl ContainerDetails {
         match action {
             Action::PreviousScreen => {
                 if let Some(tx) = self.action_tx.clone() {
-                    tx.send(Action::Screen(Component::Containers(Containers::new(
-                        Default::default(),
-                    ))))?;
+                    tx.send(Action::Back(Fallback::Containers))?;
                 }
             }
             Action::Up => {
@@ -86,7 +85,7 @@ impl ContainerDetails {
                     .title(Span::styled(
                         format!(
                             "Inspecting container: \"{}/{}\" (press 'ESC' to previous screen, 'q' to quit)",
-                            &self.cid[0..12],
+                            short_id(&self.cid),
                             self.name
                         ),
                         Style::default().add_modifier(Modifier::BOLD),
@@ -1,12 +1,20 @@
-use std::sync::Arc;
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
 use ansi_to_tui::IntoText;
 use bollard::container::LogsOptions;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Local, NaiveTime, Utc};
 use color_eyre::Result;
+use eyre::eyre;
 
-use crossterm::event::{self, KeyCode};
+use crossterm::event::{self, KeyCode, KeyEventKind};
 use futures::StreamExt;
+use regex::Regex;
 
 use futures::executor::block_on;
 use ratatui::layout::{Constraint, Layout};
@@ -15,24 +23,105 @@ use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 use tokio::{select, spawn};
-use tokio_util::sync::CancellationToken;
+use tokio_util::sync::{CancellationToken, DropGuard};
 
 use ratatui::{
-    style::{Modifier, Style, Stylize},
-    text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, ScrollbarState},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Clear, Padding, Paragraph, ScrollbarState},
 };
 
-use crate::components::{containers::Containers, Component};
-use crate::{action::Action, runtime::get_container_logs};
+use crate::components::{input::TextInput, Fallback};
+use crate::runtime::NameFilter;
+use crate::utils::{centered_rect, copy_to_clipboard, get_data_dir, safe_subslice, short_id};
+use crate::{action::Action, config, runtime::get_container_logs, tui};
+
+// Ring buffer of the log lines, the oldest ones are dropped once it is full
+#[derive(Debug, Default)]
+struct LogBuffer {
+    lines: VecDeque<String>,
+    // Number of lines dropped since the last clear, used to keep the scroll position
+    evicted: usize,
+    // Incremented on each clear
+    generation: usize,
+}
+
+impl LogBuffer {
+    fn push(&mut self, line: String, capacity: usize) {
+        while self.lines.len() >= capacity.max(1) {
+            self.lines.pop_front();
+            self.evicted += 1;
+        }
+        self.lines.push_back(line);
+    }
+
+    fn clear(&mut self) {
+        self.lines.clear();
+        self.evicted = 0;
+        self.generation += 1;
+    }
+}
+
+// Positions of the lines matching the filters, counted from the first line received so they
+// stay valid when the oldest lines are dropped, only the new lines are filtered on update
+#[derive(Clone, Debug, Default)]
+struct FilteredLines {
+    generation: usize,
+    // Position of the first line not filtered yet
+    next: usize,
+    positions: VecDeque<usize>,
+}
+
+impl FilteredLines {
+    fn update(&mut self, logs: &LogBuffer, level_filter: bool, name_filter: &Option<NameFilter>) {
+        if self.generation != logs.generation {
+            *self = FilteredLines {
+                generation: logs.generation,
+                ..Default::default()
+            };
+        }
+        while self.positions.front().is_some_and(|&p| p < logs.evicted) {
+            self.positions.pop_front();
+        }
+        let levels = &config::get().logs;
+        let start = self.next.max(logs.evicted);
+        let matching = logs
+            .lines
+            .iter()
+            .enumerate()
+            .skip(start - logs.evicted)
+            .filter(|(_, l)| !level_filter || levels.matches(l))
+            .filter(|(_, l)| match name_filter {
+                Some(f) => f.matches(l),
+                None => true,
+            })
+            .map(|(i, _)| logs.evicted + i);
+        self.positions.extend(matching);
+        self.next = logs.evicted + logs.lines.len();
+    }
+
+    fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    fn lines<'a>(&'a self, logs: &'a LogBuffer) -> impl Iterator<Item = &'a str> + 'a {
+        self.positions
+            .iter()
+            .map(|&p| logs.lines[p - logs.evicted].as_str())
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct ContainerLogs {
     id: String,
     name: String,
-    logs: Arc<Mutex<Vec<String>>>,
-    task: Arc<JoinHandle<Result<()>>>,
+    logs: Arc<Mutex<LogBuffer>>,
+    evicted: usize,
+    ended: Arc<AtomicBool>,
+    ended_notified: bool,
+    task: Option<Arc<JoinHandle<Result<()>>>>,
     cancellation_token: CancellationToken,
+    _drop_guard: Arc<DropGuard>,
     vertical_scroll_state: ScrollbarState,
     vertical_scroll: usize,
     action_tx: Option<UnboundedSender<Action>>,
@@ -40,31 +129,58 @@ pub struct ContainerLogs {
     auto_scroll: bool,
     since: i64,
     line_wrap: bool,
+    ansi: bool,
+    timestamps: bool,
+    level_filter: bool,
+    jump_to: Option<TextInput>,
+    selection_anchor: Option<usize>,
+    name_filter: Option<NameFilter>,
+    // Invalidated when the filters change
+    filtered: FilteredLines,
+    // Colors applied to the lines matching the configured patterns
+    color_rules: Vec<(Regex, Color)>,
 }
 
 async fn run_setup_task(
     cid: String,
-    follow: bool,
-    since: i64,
-    logs: Arc<Mutex<Vec<String>>>,
+    options: LogsOptions<String>,
+    logs: Arc<Mutex<LogBuffer>>,
+    ended: Arc<AtomicBool>,
+    tx: UnboundedSender<Action>,
     cancel: CancellationToken,
 ) -> Result<()> {
     let mut should_stop = false;
-    let since = (Utc::now() - Duration::minutes(since)).timestamp();
-    let options = LogsOptions {
-        stdout: true,
-        stderr: false,
-        since,
-        follow,
-        ..Default::default()
+    let capacity = config::get().logs.max_lines;
+    let mut stream = match get_container_logs(&cid, options).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            ended.store(true, Ordering::Relaxed);
+            tx.send(Action::Error(format!(
+                "Unable to get logs for container {}:\n{}",
+                cid, e
+            )))?;
+            return Ok(());
+        }
     };
-    let mut stream = get_container_logs(&cid, options).await?;
     while !should_stop {
         select!(
         l = stream.next() => {
-            if let Some(Ok(log)) = l {
-                let mut w_logs = logs.lock().await;
-                w_logs.push(log.to_string());
+            match l {
+                Some(Ok(log)) => {
+                    logs.lock().await.push(log.to_string(), capacity);
+                }
+                Some(Err(e)) => {
+                    ended.store(true, Ordering::Relaxed);
+                    should_stop = true;
+                    tx.send(Action::Error(format!(
+                        "Log stream error for container {}:\n{}",
+                        cid, e
+                    )))?;
+                }
+                None => {
+                    ended.store(true, Ordering::Relaxed);
+                    should_stop = true;
+                }
             }
         }
         _ = cancel.cancelled() => {
@@ -75,33 +191,67 @@ async fn run_setup_task(
     Ok(())
 }
 
+// Docker prefixes each line with its RFC3339 timestamp when requested
+fn line_timestamp(line: &str) -> Option<DateTime<Utc>> {
+    let (timestamp, _) = line.split_once(' ')?;
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+fn parse_time(input: &str) -> Option<DateTime<Utc>> {
+    let input = input.trim();
+    if let Ok(t) = DateTime::parse_from_rfc3339(input) {
+        return Some(t.with_timezone(&Utc));
+    }
+    if let Ok(t) = NaiveTime::parse_from_str(input, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(input, "%H:%M"))
+    {
+        return Local::now()
+            .date_naive()
+            .and_time(t)
+            .and_local_timezone(Local)
+            .single()
+            .map(|t| t.with_timezone(&Utc));
+    }
+    let duration = input.trim_end_matches("ago").trim().trim_start_matches('-');
+    let length = duration.chars().count().checked_sub(1)?;
+    let (value, unit) = (
+        safe_subslice(duration, 0, length),
+        safe_subslice(duration, length, usize::MAX),
+    );
+    let value: i64 = value.trim().parse().ok()?;
+    let duration = match unit {
+        "s" => Duration::seconds(value),
+        "m" => Duration::minutes(value),
+        "h" => Duration::hours(value),
+        "d" => Duration::days(value),
+        _ => return None,
+    };
+    Some(Utc::now() - duration)
+}
+
 impl ContainerLogs {
     pub fn new(id: String, name: String) -> Self {
-        let logs = Arc::new(Mutex::new(Vec::new()));
+        let logs = Arc::new(Mutex::new(LogBuffer::default()));
         let cancel = CancellationToken::new();
-        let _cancel = cancel.clone();
-
-        let _logs = Arc::clone(&logs);
 
-        let follow = true;
+        let ended = Arc::new(AtomicBool::new(false));
 
         let since = 15;
 
         let line_wrap = false;
 
-        let task = Arc::new(spawn(run_setup_task(
-            id.clone(),
-            follow,
-            since,
-            _logs,
-            _cancel,
-        )));
-
+        // The logs task is started once the action sender is registered
         ContainerLogs {
             id,
             name,
             logs,
-            task: Arc::clone(&task),
+            evicted: 0,
+            ended,
+            ended_notified: false,
+            task: None,
+            _drop_guard: Arc::new(cancel.clone().drop_guard()),
             cancellation_token: cancel,
             vertical_scroll_state: Default::default(),
             vertical_scroll: 0,
@@ -110,9 +260,127 @@ impl ContainerLogs {
             auto_scroll: true,
             since,
             line_wrap,
+            ansi: true,
+            timestamps: false,
+            level_filter: false,
+            jump_to: None,
+            selection_anchor: None,
+            name_filter: None,
+            filtered: FilteredLines::default(),
+            color_rules: config::get().logs.color_rules(),
+        }
+    }
+
+    fn logs_options(&self) -> LogsOptions<String> {
+        // A zero duration fetches the logs since the container started
+        let since = match self.since {
+            0 => 0,
+            _ => (Utc::now() - Duration::minutes(self.since)).timestamp(),
+        };
+        LogsOptions {
+            stdout: true,
+            stderr: false,
+            since,
+            follow: self.follow,
+            timestamps: self.timestamps,
+            ..Default::default()
         }
     }
 
+    fn start(&mut self) {
+        let tx = self.action_tx.clone().expect("No action sender");
+        let cancel = CancellationToken::new();
+        let _cancel = cancel.clone();
+
+        let _logs = Arc::clone(&self.logs);
+
+        self.ended.store(false, Ordering::Relaxed);
+        self.ended_notified = false;
+        let task = Arc::new(spawn(run_setup_task(
+            self.id.clone(),
+            self.logs_options(),
+            _logs,
+            Arc::clone(&self.ended),
+            tx,
+            _cancel,
+        )));
+
+        self.task = Some(task);
+        self._drop_guard = Arc::new(cancel.clone().drop_guard());
+        self.cancellation_token = cancel;
+    }
+
+    async fn restart(&mut self) -> Result<()> {
+        self.cancel()?;
+        self.logs.lock().await.clear();
+        self.evicted = 0;
+        self.start();
+        Ok(())
+    }
+
+    fn jump_to(&mut self, input: &str) -> Result<()> {
+        let target = parse_time(input).ok_or(eyre!(
+            "Unable to parse \"{}\", expected an RFC3339 date, a time (HH:MM[:SS]) or a duration (e.g. 5m)",
+            input
+        ))?;
+        let logs = block_on(self.logs.lock());
+        self.filtered
+            .update(&logs, self.level_filter, &self.name_filter);
+        let index = self
+            .filtered
+            .lines(&logs)
+            .position(|l| line_timestamp(l).is_some_and(|t| t >= target))
+            .ok_or(eyre!(
+                "No log line at or after {}",
+                target.with_timezone(&Local)
+            ))?;
+        drop(logs);
+        self.auto_scroll = false;
+        self.vertical_scroll = index;
+        self.vertical_scroll_state = self.vertical_scroll_state.position(index);
+        Ok(())
+    }
+
+    fn selection(&self) -> Option<(usize, usize)> {
+        self.selection_anchor
+            .map(|a| (a.min(self.vertical_scroll), a.max(self.vertical_scroll)))
+    }
+
+    fn yank(&mut self) -> Result<usize> {
+        let Some((start, end)) = self.selection() else {
+            return Ok(0);
+        };
+        let logs = block_on(self.logs.lock());
+        self.filtered
+            .update(&logs, self.level_filter, &self.name_filter);
+        let selected = self
+            .filtered
+            .lines(&logs)
+            .skip(start)
+            .take(end - start + 1)
+            .map(strip_ansi_escapes::strip_str)
+            .collect::<Vec<String>>();
+        drop(logs);
+        copy_to_clipboard(&selected.concat())?;
+        self.selection_anchor = None;
+        Ok(selected.len())
+    }
+
+    async fn export(&mut self) -> Result<std::path::PathBuf> {
+        let logs = self.logs.lock().await;
+        let dir = get_data_dir().join("logs");
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!(
+            "{}-{}.log",
+            self.name,
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        self.filtered
+            .update(&logs, self.level_filter, &self.name_filter);
+        std::fs::write(&path, self.filtered.lines(&logs).collect::<String>())?;
+        Ok(path)
+    }
+
     fn down(&mut self, qty: usize) {
         self.vertical_scroll = self.vertical_scroll.saturating_add(qty);
         self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
@@ -125,7 +393,26 @@ impl ContainerLogs {
 
     fn cancel(&mut self) -> Result<()> {
         self.cancellation_token.cancel();
-        self.task.abort();
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+        Ok(())
+    }
+
+    pub(crate) fn pause(&mut self) -> Result<()> {
+        self.cancel()
+    }
+
+    // The logs are fetched again, the lines written while paused are not lost
+    pub(crate) fn resume(&mut self) -> Result<()> {
+        block_on(self.logs.lock()).clear();
+        self.evicted = 0;
+        self.start();
+        Ok(())
+    }
+
+    pub(crate) fn teardown(&mut self, _t: &mut tui::Tui) -> Result<()> {
+        self.cancel()?;
         Ok(())
     }
 
@@ -135,16 +422,28 @@ impl ContainerLogs {
 
     pub(crate) fn register_action_handler(&mut self, action_tx: UnboundedSender<Action>) {
         self.action_tx = Some(action_tx);
+        self.start();
     }
 
     pub(crate) async fn update(&mut self, action: Action) -> Result<()> {
         let tx = self.action_tx.clone().expect("No action sender");
         match action {
+            Action::Tick if self.ended.load(Ordering::Relaxed) && !self.ended_notified => {
+                self.ended_notified = true;
+                self.auto_scroll = false;
+                if self.follow {
+                    tx.send(Action::Info(format!(
+                        "Container \"{}\" stopped or was removed",
+                        self.name
+                    )))?;
+                }
+            }
+            Action::PreviousScreen if self.selection_anchor.is_some() => {
+                self.selection_anchor = None;
+            }
             Action::PreviousScreen => {
                 self.cancel()?;
-                tx.send(Action::Screen(Component::Containers(Containers::new(
-                    Default::default(),
-                ))))?;
+                tx.send(Action::Back(Fallback::Containers))?;
             }
             Action::Up => {
                 self.auto_scroll = false;
@@ -164,25 +463,28 @@ impl ContainerLogs {
             }
             Action::Since(n) => {
                 log::debug!("****** Since {}", n);
-                self.cancel()?;
-                self.logs.lock().await.clear();
-
-                let cancel = CancellationToken::new();
-                let _cancel = cancel.clone();
-
-                let _logs = Arc::clone(&self.logs);
-
-                let task = Arc::new(spawn(run_setup_task(
-                    self.id.clone(),
-                    self.follow,
-                    n.into(),
-                    _logs,
-                    _cancel,
-                )));
-
-                self.task = Arc::clone(&task);
-                self.cancellation_token = cancel;
                 self.since = n as i64;
+                self.restart().await?;
+            }
+            Action::Timestamps => {
+                self.timestamps = !self.timestamps;
+                self.restart().await?;
+            }
+            Action::JumpTo => {
+                if self.timestamps {
+                    self.jump_to = Some(TextInput::default());
+                } else {
+                    tx.send(Action::Info(
+                        "Enable timestamps with 't' before jumping to a time".to_string(),
+                    ))?;
+                }
+            }
+            Action::Ok => {
+                if let Some(input) = self.jump_to.take() {
+                    if let Err(e) = self.jump_to(input.value()) {
+                        tx.send(Action::Error(e.to_string()))?;
+                    }
+                }
             }
             Action::AutoScroll => {
                 self.auto_scroll = !self.auto_scroll;
@@ -190,6 +492,37 @@ impl ContainerLogs {
             Action::LineWrap => {
                 self.line_wrap = !self.line_wrap;
             }
+            Action::Visual => {
+                self.selection_anchor = match self.selection_anchor {
+                    Some(_) => None,
+                    None => {
+                        self.auto_scroll = false;
+                        Some(self.vertical_scroll)
+                    }
+                };
+            }
+            Action::Yank => match self.yank() {
+                Ok(0) => {}
+                Ok(n) => tx.send(Action::Info(format!("Copied {} lines to the clipboard", n)))?,
+                Err(e) => tx.send(Action::Error(format!("Unable to copy logs:\n{}", e)))?,
+            },
+            Action::LevelFilter => {
+                self.level_filter = !self.level_filter;
+                self.filtered = FilteredLines::default();
+            }
+            Action::Ansi => {
+                self.ansi = !self.ansi;
+            }
+            Action::SetNameFilter(name_filter) => {
+                self.name_filter = name_filter;
+                self.filtered = FilteredLines::default();
+            }
+            Action::Export => match self.export().await {
+                Ok(path) => {
+                    tx.send(Action::Info(format!("Logs exported to {}", path.display())))?
+                }
+                Err(e) => tx.send(Action::Error(format!("Unable to export logs:\n{}", e)))?,
+            },
             _ => {}
         }
         Ok(())
@@ -205,27 +538,126 @@ impl ContainerLogs {
             .split(area);
 
         let logs = block_on(self.logs.lock());
-        let first_line = Paragraph::new(Line::from(vec![
+        let mut status = vec![
             Span::from("Autoscroll: "),
             Span::styled(
                 if self.auto_scroll { "On" } else { "Off" },
                 Style::new().bold(),
             ),
+            Span::from(" - Stream: "),
+            Span::styled(
+                if self.ended.load(Ordering::Relaxed) {
+                    "Ended"
+                } else {
+                    "Following"
+                },
+                Style::new().bold(),
+            ),
             Span::from(" - Since: "),
-            Span::styled(format!("{}m", self.since), Style::new().bold()),
+            Span::styled(
+                match self.since {
+                    0 => "start".to_string(),
+                    n => format!("{}m", n),
+                },
+                Style::new().bold(),
+            ),
             Span::from(" - Line wrap: "),
             Span::styled(
                 if self.line_wrap { "On" } else { "Off" },
                 Style::new().bold(),
             ),
-        ]))
-        .block(Block::default().borders(Borders::NONE).gray());
-        let text = logs.join("").into_text().expect("Cannot parse logs");
+            Span::from(" - Levels: "),
+            Span::styled(
+                if self.level_filter {
+                    config::get().logs.levels.join("|")
+                } else {
+                    "All".to_string()
+                },
+                Style::new().bold(),
+            ),
+            Span::from(" - Timestamps: "),
+            Span::styled(
+                if self.timestamps { "On" } else { "Off" },
+                Style::new().bold(),
+            ),
+            Span::from(" - ANSI colors: "),
+            Span::styled(if self.ansi { "On" } else { "Off" }, Style::new().bold()),
+            Span::from(
+                self.name_filter
+                    .as_ref()
+                    .map(NameFilter::format)
+                    .unwrap_or_default(),
+            ),
+            Span::from(
+                self.selection()
+                    .map(|(start, end)| format!(" - Visual: {} lines", end - start + 1))
+                    .unwrap_or_default(),
+            ),
+        ];
+        if !self.color_rules.is_empty() {
+            status.push(Span::from(" - Colors: "));
+            for (i, (regex, color)) in self.color_rules.iter().enumerate() {
+                if i > 0 {
+                    status.push(Span::from(" "));
+                }
+                status.push(Span::styled(regex.to_string(), Style::new().fg(*color)));
+            }
+        }
+        let first_line = Paragraph::new(Line::from(status))
+            .block(Block::default().borders(Borders::NONE).gray());
+        // Keep the same lines on screen when the oldest ones were dropped, with a filter the
+        // dropped lines may not all have been displayed so the position is an approximation
+        let evicted = logs.evicted.saturating_sub(self.evicted);
+        self.evicted = logs.evicted;
+        self.filtered
+            .update(&logs, self.level_filter, &self.name_filter);
+        let height = usize::from(rects[1].height.saturating_sub(2));
+        if self.auto_scroll {
+            self.vertical_scroll = self.filtered.len().saturating_sub(height);
+        } else {
+            self.vertical_scroll = self
+                .vertical_scroll
+                .saturating_sub(evicted)
+                .min(self.filtered.len().saturating_sub(1));
+            self.selection_anchor = self.selection_anchor.map(|a| a.saturating_sub(evicted));
+        }
+        // Only the lines fitting in the area are turned into text, a wrapped line takes at
+        // least one row so none of them can be missing
+        let visible = self
+            .filtered
+            .lines(&logs)
+            .skip(self.vertical_scroll)
+            .take(height)
+            .collect::<String>();
+        let mut text = if self.ansi {
+            visible.into_text().expect("Cannot parse logs")
+        } else {
+            // Show the escape sequences instead of sending them to the terminal
+            Text::raw(visible.replace('\x1b', "^["))
+        };
+        for line in text.lines.iter_mut() {
+            let content: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+            if let Some((_, color)) = self.color_rules.iter().find(|(r, _)| r.is_match(&content)) {
+                // Colors emitted by the application are kept
+                line.spans
+                    .iter_mut()
+                    .filter(|s| s.style.fg.is_none())
+                    .for_each(|s| s.style.fg = Some(*color));
+            }
+        }
+        if let Some((start, end)) = self.selection() {
+            text.lines
+                .iter_mut()
+                .enumerate()
+                .map(|(i, l)| (i + self.vertical_scroll, l))
+                .filter(|(i, _)| (start..=end).contains(i))
+                .for_each(|(_, l)| l.patch_style(Style::new().reversed()));
+        }
         let mut log_paragraph =
             Paragraph::new(text).block(Block::default().borders(Borders::ALL).title(Span::styled(
                 format!(
                     "Container logs for: \"{}/{}\" (press 'ESC' to previous screen, 'q' to quit)",
-                    &self.id[0..12],
+                    short_id(&self.id),
                     self.name
                 ),
                 Style::default().add_modifier(Modifier::BOLD),
@@ -233,25 +665,78 @@ impl ContainerLogs {
         if self.line_wrap {
             log_paragraph = log_paragraph.wrap(Wrap { trim: false });
         }
-        if self.auto_scroll {
-            let lines = area.height - 2;
-            self.vertical_scroll = logs.len().saturating_sub(lines.into());
-        }
-        log_paragraph = log_paragraph.scroll((self.vertical_scroll as u16, 0));
 
         f.render_widget(first_line, rects[0]);
         f.render_widget(log_paragraph, rects[1]);
+
+        if let Some(input) = &self.jump_to {
+            let text = vec![
+                Line::from("Jump to the first line at or after:"),
+                Line::from(""),
+                Line::from(format!("> {}", input.value())),
+                Line::from(Span::styled(
+                    "e.g. 2024-01-31T12:00:00Z, 12:00, 12:00:30, 5m or 2h ago",
+                    Style::new().dark_gray(),
+                )),
+                Line::from(vec![
+                    "ESC".bold(),
+                    " to Cancel, ".into(),
+                    "Enter".bold(),
+                    " to Confirm".into(),
+                ]),
+            ];
+            let paragraph = Paragraph::new(text).wrap(Wrap { trim: false });
+            let block = Block::default()
+                .title("Jump to time".bold())
+                .padding(Padding::new(1, 1, 1, 1))
+                .borders(Borders::ALL);
+            let area = centered_rect(50, 10, f.size());
+            f.render_widget(Clear, area);
+            f.render_widget(paragraph.block(block), area);
+        }
+    }
+
+    pub(crate) fn handle_input(
+        &mut self,
+        kevent: event::KeyEvent,
+    ) -> Result<Option<event::KeyEvent>> {
+        match &mut self.jump_to {
+            Some(input) if kevent.kind == KeyEventKind::Press => match kevent.code {
+                KeyCode::Esc => {
+                    self.jump_to = None;
+                    Ok(None)
+                }
+                _ if input.handle_key(&kevent) => Ok(None),
+                _ => Ok(Some(kevent)),
+            },
+            Some(_) => Ok(None),
+            None => Ok(Some(kevent)),
+        }
+    }
+
+    pub(crate) fn handle_paste(&mut self, text: &str) {
+        if let Some(input) = &mut self.jump_to {
+            input.paste(text);
+        }
     }
 
     pub(crate) fn get_bindings(&self) -> Option<&[(&str, &str)]> {
         Some(&[
             ("s", "Autoscroll"),
             ("w", "Line wrap"),
+            ("e", "Export logs to a file"),
+            ("r", "Toggle ANSI colors/raw escapes"),
+            ("t", "Timestamps"),
+            ("L", "Warnings/errors only"),
+            ("v", "Visual line selection"),
+            ("y", "Copy selected lines"),
+            ("T", "Jump to time"),
             ("1", "Since 1m"),
             ("2", "Since 3m"),
             ("3", "Since 5m"),
             ("4", "Since 10m"),
             ("5", "Since 15m"),
+            ("0", "Since container start"),
         ])
     }
 
@@ -259,12 +744,24 @@ impl ContainerLogs {
         match k.code {
             KeyCode::Char('s') => Some(Action::AutoScroll),
             KeyCode::Char('w') => Some(Action::LineWrap),
+            KeyCode::Char('e') => Some(Action::Export),
+            KeyCode::Char('r') => Some(Action::Ansi),
+            KeyCode::Char('t') => Some(Action::Timestamps),
+            KeyCode::Char('L') => Some(Action::LevelFilter),
+            KeyCode::Char('v') => Some(Action::Visual),
+            KeyCode::Char('y') => Some(Action::Yank),
+            KeyCode::Char('T') => Some(Action::JumpTo),
             KeyCode::Char('1') => Some(Action::Since(1)),
             KeyCode::Char('2') => Some(Action::Since(3)),
             KeyCode::Char('3') => Some(Action::Since(5)),
             KeyCode::Char('4') => Some(Action::Since(10)),
             KeyCode::Char('5') => Some(Action::Since(15)),
+            KeyCode::Char('0') => Some(Action::Since(0)),
             _ => None,
         }
     }
+
+    pub(crate) fn has_filter(&self) -> bool {
+        true
+    }
 }
@@ -0,0 +1,278 @@
+use std::sync::Arc;
+
+use bollard::container::StatsOptions;
+use color_eyre::Result;
+use futures::{executor::block_on, StreamExt};
+use humansize::{format_size, FormatSizeOptions, BINARY};
+use ratatui::{
+    prelude::*,
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType},
+};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio::{select, spawn};
+use tokio_util::sync::{CancellationToken, DropGuard};
+
+use crate::action::Action;
+use crate::components::Fallback;
+use crate::runtime::{
+    docker::{compute_blkio, compute_cpu, compute_mem, compute_net, compute_read_time},
+    get_container_stats, min_max_avg, ContainerMetrics,
+};
+use crate::tui;
+use crate::utils::short_id;
+
+const STATS_CAPACITY: usize = 300;
+
+#[derive(Clone, Debug)]
+pub struct ContainerStats {
+    cid: String,
+    name: String,
+    metrics: Arc<Mutex<ContainerMetrics>>,
+    task: Option<Arc<JoinHandle<Result<()>>>>,
+    cancellation_token: CancellationToken,
+    _drop_guard: Arc<DropGuard>,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+pub(crate) async fn stream_stats(
+    cid: String,
+    metrics: Arc<Mutex<ContainerMetrics>>,
+    cancel: CancellationToken,
+    tx: UnboundedSender<Action>,
+) -> Result<()> {
+    let mut should_stop = false;
+    let options = Some(StatsOptions {
+        stream: true,
+        one_shot: false,
+    });
+    let report = |e| {
+        Action::Error(format!(
+            "Unable to get the stats of container \"{}\":\n{}",
+            short_id(&cid),
+            e
+        ))
+    };
+    let mut stream = match get_container_stats(&cid, options).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            tx.send(report(e))?;
+            return Ok(());
+        }
+    };
+    while !should_stop {
+        select!(
+        s = stream.next() => {
+            match s {
+                Some(Ok(stats)) => {
+                    let mut metrics = metrics.lock().await;
+                    metrics.push_metrics(compute_cpu(&stats), compute_mem(&stats));
+                    metrics.push_io(
+                        compute_read_time(&stats),
+                        compute_net(&stats),
+                        compute_blkio(&stats),
+                    );
+                }
+                // Reported once, the following samples would most likely fail the same way
+                Some(Err(e)) => {
+                    tx.send(report(e))?;
+                    should_stop = true;
+                }
+                None => {
+                    should_stop = true;
+                }
+            }
+        }
+        _ = cancel.cancelled() => {
+            should_stop = true;
+        }
+        );
+    }
+    Ok(())
+}
+
+impl ContainerStats {
+    pub fn new(cid: String, name: String) -> Self {
+        let metrics = Arc::new(Mutex::new(ContainerMetrics::new(
+            cid.clone(),
+            STATS_CAPACITY,
+        )));
+        let cancel = CancellationToken::new();
+
+        ContainerStats {
+            cid,
+            name,
+            metrics,
+            task: None,
+            _drop_guard: Arc::new(cancel.clone().drop_guard()),
+            cancellation_token: cancel,
+            action_tx: None,
+        }
+    }
+
+    fn cancel(&mut self) -> Result<()> {
+        self.cancellation_token.cancel();
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+        Ok(())
+    }
+
+    // The stream starts once the action sender is known, to report its errors
+    fn stream(&mut self) -> Result<()> {
+        self.cancel()?;
+        if let Some(tx) = self.action_tx.clone() {
+            let cancel = CancellationToken::new();
+            self.task = Some(Arc::new(spawn(stream_stats(
+                self.cid.clone(),
+                Arc::clone(&self.metrics),
+                cancel.clone(),
+                tx,
+            ))));
+            self._drop_guard = Arc::new(cancel.clone().drop_guard());
+            self.cancellation_token = cancel;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn get_name(&self) -> &'static str {
+        "ContainerStats"
+    }
+
+    pub(crate) fn register_action_handler(&mut self, action_tx: UnboundedSender<Action>) {
+        self.action_tx = Some(action_tx);
+        if let Err(e) = self.stream() {
+            log::error!("Unable to stream the stats of {}: {}", self.cid, e);
+        }
+    }
+
+    pub(crate) async fn update(&mut self, action: Action) -> Result<()> {
+        let tx = self.action_tx.clone().expect("No action sender");
+        if let Action::PreviousScreen = action {
+            self.cancel()?;
+            tx.send(Action::Back(Fallback::ContainerView(self.cid.to_string())))?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn pause(&mut self) -> Result<()> {
+        self.cancel()
+    }
+
+    pub(crate) fn resume(&mut self) -> Result<()> {
+        self.stream()
+    }
+
+    pub(crate) fn teardown(&mut self, _t: &mut tui::Tui) -> Result<()> {
+        self.cancel()?;
+        Ok(())
+    }
+
+    pub(crate) fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let metrics = block_on(self.metrics.lock()).clone();
+        let rects = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+
+        let cpu: Vec<(f64, f64)> = metrics
+            .cpu_data()
+            .enumerate()
+            .map(|(i, v)| (-(i as f64), *v))
+            .collect();
+        let cpu_summary = min_max_avg(metrics.cpu_data().copied());
+        let cpu_title = match cpu_summary {
+            Some((min, max, avg)) => {
+                format!("CPU - min: {:.1}% max: {:.1}% avg: {:.1}%", min, max, avg)
+            }
+            None => "CPU - no data".to_string(),
+        };
+        let cpu_max = cpu_summary.map(|(_, max, _)| max).unwrap_or_default();
+        let cpu_bound = if cpu_max > 0.0 { cpu_max * 1.1 } else { 1.0 };
+        f.render_widget(
+            self.chart(
+                cpu_title,
+                &cpu,
+                metrics.capacity(),
+                cpu_bound,
+                vec![Span::from("0%"), Span::from(format!("{:.1}%", cpu_bound))],
+            ),
+            rects[0],
+        );
+
+        let mem: Vec<(f64, f64)> = metrics
+            .mem_data()
+            .enumerate()
+            .map(|(i, v)| (-(i as f64), *v as f64))
+            .collect();
+        let format = FormatSizeOptions::from(BINARY).decimal_places(1);
+        let mem_summary = min_max_avg(metrics.mem_data().map(|m| *m as f64));
+        let mem_title = match mem_summary {
+            Some((min, max, avg)) => format!(
+                "Memory - min: {} max: {} avg: {}",
+                format_size(min as u64, format),
+                format_size(max as u64, format),
+                format_size(avg as u64, format)
+            ),
+            None => "Memory - no data".to_string(),
+        };
+        let mem_max = mem_summary.map(|(_, max, _)| max).unwrap_or_default();
+        let mem_bound = if mem_max > 0.0 { mem_max * 1.1 } else { 1.0 };
+        f.render_widget(
+            self.chart(
+                mem_title,
+                &mem,
+                metrics.capacity(),
+                mem_bound,
+                vec![
+                    Span::from("0"),
+                    Span::from(format_size(mem_bound as u64, format)),
+                ],
+            ),
+            rects[1],
+        );
+    }
+
+    fn chart<'a>(
+        &self,
+        title: String,
+        data: &'a [(f64, f64)],
+        capacity: usize,
+        y_bound: f64,
+        y_labels: Vec<Span<'a>>,
+    ) -> Chart<'a> {
+        let dataset = Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().cyan())
+            .data(data);
+        Chart::new(vec![dataset])
+            .block(
+                Block::default().borders(Borders::ALL).title(Span::styled(
+                    format!(
+                        "Stats for container: \"{}/{}\" - {} (press 'ESC' to previous screen, 'q' to quit)",
+                        short_id(&self.cid),
+                        self.name,
+                        title
+                    ),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )),
+            )
+            .x_axis(
+                Axis::default()
+                    .style(Style::default().gray())
+                    .bounds([-(capacity as f64), 0.0])
+                    .labels(vec![
+                        Span::from(format!("-{}s", capacity)),
+                        Span::from("now"),
+                    ]),
+            )
+            .y_axis(
+                Axis::default()
+                    .style(Style::default().gray())
+                    .bounds([0.0, y_bound])
+                    .labels(y_labels),
+            )
+    }
+}
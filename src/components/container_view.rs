@@ -1,21 +1,45 @@
+use std::sync::Arc;
+
 use color_eyre::Result;
 
-use crossterm::event;
+use crossterm::event::{self, KeyCode, KeyEventKind, KeyModifiers};
+use futures::executor::block_on;
+use humansize::{format_size, FormatSizeOptions, BINARY};
 
 use ratatui::layout::{Constraint, Direction, Layout};
 use ratatui::style::{Modifier, Style, Stylize};
 use ratatui::text::{Line, Span, Text};
-use ratatui::widgets::{Block, Borders, Paragraph, Row, TableState};
+use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph, Row, TableState, Wrap};
+use tokio::spawn;
 use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio_util::sync::{CancellationToken, DropGuard};
 
 use crate::action::Action;
-use crate::components::{containers::Containers, Component};
-use crate::runtime::{get_container_details, ContainerDetails};
-use crate::utils::table;
+use crate::components::{
+    container_diff::ContainerDiff,
+    container_stats::{stream_stats, ContainerStats},
+    input::TextInput,
+    Component, Fallback,
+};
+use crate::runtime::{
+    commit_container, get_container_changes, get_container_details, get_container_processes,
+    ContainerDetails, ContainerMetrics, ContainerStatus, NameFilter,
+};
+use crate::tui;
+use crate::utils::{centered_rect, copy_to_clipboard, format_rates, short_id, table};
+
+// The full inspect is only refreshed every few ticks, processes are refreshed on each tick
+pub(crate) const DETAILS_REFRESH_TICKS: usize = 5;
 
-const CONTAINER_PROCESSES_CONSTRAINTS: [Constraint; 3] = [
-    Constraint::Min(10),
-    Constraint::Min(10),
+const CONTAINER_PROCESSES_CONSTRAINTS: [Constraint; 7] = [
+    Constraint::Max(10),
+    Constraint::Max(8),
+    Constraint::Max(6),
+    Constraint::Max(6),
+    Constraint::Max(6),
+    Constraint::Max(8),
     Constraint::Min(20),
 ];
 
@@ -25,16 +49,100 @@ pub struct ContainerView {
     details: Option<ContainerDetails>,
     action_tx: Option<UnboundedSender<Action>>,
     state: TableState,
+    metrics: Arc<Mutex<ContainerMetrics>>,
+    task: Option<Arc<JoinHandle<Result<()>>>>,
+    cancellation_token: CancellationToken,
+    _drop_guard: Arc<DropGuard>,
+    commit: Option<TextInput>,
+    ticks: usize,
+    // Narrows the displayed environment variables
+    env_filter: Option<NameFilter>,
+    // Show the values of the variables matching `containers.masked_env`
+    reveal: bool,
+}
+
+// A fuzzy match on the values is too loose, the variables are searched for a substring
+fn env_matches(filter: &NameFilter, entry: &str) -> bool {
+    match filter {
+        NameFilter::Fuzzy(pattern) => entry.to_lowercase().contains(&pattern.to_lowercase()),
+        NameFilter::Regex(_) => filter.matches(entry),
+    }
+}
+
+async fn commit(
+    cid: String,
+    name: String,
+    reference: String,
+    tx: UnboundedSender<Action>,
+) -> Result<()> {
+    let (repo, tag) = match reference.rsplit_once(':') {
+        Some((repo, tag)) if !tag.contains('/') => (repo, tag),
+        _ => (reference.as_str(), "latest"),
+    };
+    match commit_container(&cid, repo, tag).await {
+        Ok(()) => tx.send(Action::Info(format!(
+            "Container \"{}\" committed to image \"{}:{}\"",
+            name, repo, tag
+        )))?,
+        Err(e) => tx.send(Action::Error(format!(
+            "Unable to commit container \"{}\":\n{}",
+            name, e
+        )))?,
+    }
+    Ok(())
 }
 
 impl ContainerView {
     pub fn new(id: String) -> Self {
+        let metrics = Arc::new(Mutex::new(ContainerMetrics::new(id.clone(), 1)));
+        let cancel = CancellationToken::new();
+
         ContainerView {
             id,
             details: None,
             action_tx: None,
             state: TableState::new(),
+            metrics,
+            task: None,
+            _drop_guard: Arc::new(cancel.clone().drop_guard()),
+            cancellation_token: cancel,
+            commit: None,
+            ticks: 0,
+            env_filter: None,
+            reveal: false,
+        }
+    }
+
+    fn name(&self) -> String {
+        self.details
+            .as_ref()
+            .map(|d| d.name.to_string())
+            .unwrap_or(short_id(&self.id).to_string())
+    }
+
+    fn cancel(&mut self) -> Result<()> {
+        self.cancellation_token.cancel();
+        if let Some(task) = &self.task {
+            task.abort();
         }
+        Ok(())
+    }
+
+    // The stream starts once the action sender is known, to report its errors
+    fn stream(&mut self) -> Result<()> {
+        self.cancel()?;
+        if let Some(tx) = self.action_tx.clone() {
+            let cancel = CancellationToken::new();
+            self.task = Some(Arc::new(spawn(stream_stats(
+                self.id.clone(),
+                Arc::clone(&self.metrics),
+                cancel.clone(),
+                tx,
+            ))));
+            self._drop_guard = Arc::new(cancel.clone().drop_guard());
+            self.cancellation_token = cancel;
+        }
+        Ok(())
     }
 
     pub(crate) fn get_name(&self) -> &'static str {
@@ -43,33 +151,173 @@ impl ContainerView {
 
     pub(crate) fn register_action_handler(&mut self, action_tx: UnboundedSender<Action>) {
         self.action_tx = Some(action_tx);
+        if let Err(e) = self.stream() {
+            log::error!("Unable to stream the stats of {}: {}", self.id, e);
+        }
     }
 
     pub(crate) async fn update(&mut self, action: Action) -> Result<()> {
         let tx = self.action_tx.clone().expect("No action sender");
         match action {
             Action::PreviousScreen => {
-                tx.send(Action::Screen(Component::Containers(Containers::new(
-                    Default::default(),
-                ))))?;
+                self.cancel()?;
+                tx.send(Action::Back(Fallback::Containers))?;
             }
-            Action::Tick => match get_container_details(&self.id).await {
-                Ok(details) => self.details = Some(details),
-                Err(e) => {
-                    tx.send(Action::Error(e.to_string()))?;
-                    self.details = None;
+            Action::Tick if self.details.is_none() || self.ticks >= DETAILS_REFRESH_TICKS => {
+                self.ticks = 1;
+                match get_container_details(&self.id).await {
+                    Ok(details) => self.details = Some(details),
+                    Err(e) => {
+                        tx.send(Action::Error(e.to_string()))?;
+                        self.details = None;
+                    }
                 }
-            },
+            }
+            Action::Tick => {
+                self.ticks += 1;
+                if let Some(details) = self.details.as_mut() {
+                    if let ContainerStatus::Running(_) = details.status {
+                        details.processes =
+                            get_container_processes(&self.id).await.unwrap_or_default();
+                    }
+                }
+            }
+            Action::Changes => {
+                let name = self.name();
+                let action =
+                    match get_container_changes(&self.id).await {
+                        Ok(changes) => Action::Screen(Component::ContainerDiff(
+                            ContainerDiff::new(self.id.to_string(), name, changes),
+                        )),
+                        Err(e) => Action::Error(format!(
+                            "Unable to get container \"{}\" filesystem changes:\n{}",
+                            name, e
+                        )),
+                    };
+                tx.send(action)?;
+            }
+            Action::Commit => {
+                self.commit = Some(TextInput::default());
+            }
+            Action::Ok => {
+                if let Some(reference) = self.commit.take() {
+                    tx.send(Action::Info(format!(
+                        "Committing container \"{}\"...",
+                        self.name()
+                    )))?;
+                    spawn(commit(
+                        self.id.clone(),
+                        self.name(),
+                        reference.value().to_string(),
+                        tx.clone(),
+                    ));
+                }
+            }
+            Action::SetNameFilter(env_filter) => {
+                self.env_filter = env_filter;
+            }
+            // Variables are displayed as `KEY: value` but commonly searched as `KEY=value`
+            Action::SetFilter(filter) => {
+                self.env_filter = filter.map(NameFilter::Fuzzy);
+            }
+            Action::CopyIp | Action::CopyPort => {
+                let value = self.details.as_ref().and_then(|d| match action {
+                    Action::CopyIp => d.primary_ip(),
+                    _ => d.published_port(),
+                });
+                let what = if let Action::CopyIp = action {
+                    "IP address"
+                } else {
+                    "published port"
+                };
+                let action = match value.map(|v| (v, copy_to_clipboard(v))) {
+                    Some((v, Ok(()))) => {
+                        Action::Info(format!("Copied {} {} to the clipboard", what, v))
+                    }
+                    Some((_, Err(e))) => {
+                        Action::Error(format!("Unable to copy the {}:\n{}", what, e))
+                    }
+                    None => Action::Error(format!("Container \"{}\" has no {}", self.name(), what)),
+                };
+                tx.send(action)?;
+            }
+            Action::Reveal => {
+                self.reveal = !self.reveal;
+            }
+            Action::Stats => {
+                let name = self.name();
+                tx.send(Action::Screen(Component::ContainerStats(
+                    ContainerStats::new(self.id.to_string(), name),
+                )))?;
+            }
             _ => {}
         }
         Ok(())
     }
 
+    pub(crate) fn pause(&mut self) -> Result<()> {
+        self.cancel()
+    }
+
+    pub(crate) fn resume(&mut self) -> Result<()> {
+        self.stream()
+    }
+
+    pub(crate) fn teardown(&mut self, _t: &mut tui::Tui) -> Result<()> {
+        self.cancel()?;
+        Ok(())
+    }
+
+    fn stats_line(&self) -> Line<'static> {
+        let metrics = block_on(self.metrics.lock());
+        let format = FormatSizeOptions::from(BINARY).decimal_places(1);
+        let cpu = metrics
+            .cpu_data()
+            .next()
+            .map(|cpu| format!("{:.1}%", cpu))
+            .unwrap_or("-".to_string());
+        let mem = metrics
+            .mem_data()
+            .next()
+            .map(|mem| format_size(*mem, format))
+            .unwrap_or("-".to_string());
+        let net = metrics
+            .net_data()
+            .next()
+            .map(|(rx, tx)| format_rates(*rx, *tx))
+            .unwrap_or("-".to_string());
+        let blkio = metrics
+            .blkio_data()
+            .next()
+            .map(|(read, write)| format_rates(*read, *write))
+            .unwrap_or("-".to_string());
+        Line::from(vec![
+            Span::styled("CPU: ", Style::default().bold()),
+            Span::raw(format!("{}  ", cpu)),
+            Span::styled("MEM: ", Style::default().bold()),
+            Span::raw(format!("{}  ", mem)),
+            Span::styled("NET: ", Style::default().bold()),
+            Span::raw(format!("{}  ", net)),
+            Span::styled("BLOCK: ", Style::default().bold()),
+            Span::raw(blkio),
+        ])
+    }
+
     pub(crate) fn draw(
         &mut self,
         f: &mut ratatui::prelude::Frame<'_>,
         area: ratatui::prelude::Rect,
     ) {
+        let rects = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(area);
+        let stats = Paragraph::new(self.stats_line())
+            .gray()
+            .block(Block::default().borders(Borders::ALL).title("Stats"));
+        f.render_widget(stats, rects[0]);
+        let area = rects[1];
+
         let nb_processes = self
             .details
             .as_ref()
@@ -94,18 +342,27 @@ impl ContainerView {
         let text: Vec<Line> = self
             .details
             .as_ref()
-            .map(|d| d.into())
+            .map(|d| {
+                // Masked first so the filter can't be used to guess the hidden values
+                let mut d = if self.reveal { d.clone() } else { d.masked() };
+                if let Some(filter) = &self.env_filter {
+                    d.env
+                        .retain(|(k, v)| env_matches(filter, &format!("{}={}", k, v)));
+                }
+                (&d).into()
+            })
             .unwrap_or(vec![Line::from("Unable to get container details")]);
 
         let details = Paragraph::new(Text::from(text)).block(
             Block::default().borders(Borders::ALL).title(Span::styled(
                 format!(
-                    "Inspecting container: \"{}/{}\" (press 'ESC' to previous screen, 'q' to quit)",
-                    &self.id[0..12],
-                    self.details
-                        .clone()
-                        .map(|d| d.name)
-                        .unwrap_or(String::from("<UNKNOWN>"))
+                    "Inspecting container: \"{}/{}\"{} (press 'ESC' to previous screen, 'q' to quit)",
+                    short_id(&self.id),
+                    self.name(),
+                    self.env_filter
+                        .as_ref()
+                        .map(NameFilter::format)
+                        .unwrap_or_default()
                 ),
                 Style::default().add_modifier(Modifier::BOLD),
             )),
@@ -115,32 +372,96 @@ impl ContainerView {
         if nb_processes > 0 {
             let t = table(
                 "Processes".into(),
-                ["UID", "HOST_PID", "PROCESS"],
+                &[
+                    "USER", "HOST_PID", "%CPU", "%MEM", "STAT", "START", "COMMAND",
+                ],
                 self.details
                     .as_ref()
-                    .map(|details| {
-                        details
-                            .processes
-                            .iter()
-                            .map(|(uid, pid, cmd)| {
-                                Row::new(vec![uid.to_string(), pid.to_string(), cmd.to_string()])
-                                    .style(Style::default().gray())
-                            })
-                            .collect()
-                    })
+                    .map(|details| details.processes.iter().map(Row::from).collect())
                     .unwrap_or_default(),
                 &CONTAINER_PROCESSES_CONSTRAINTS,
                 Some(Style::new().gray()),
+                None,
             );
             f.render_stateful_widget(t, ps_area, &mut self.state);
         }
+
+        if let Some(reference) = &self.commit {
+            let text = vec![
+                Line::from("Repository and tag of the new image:"),
+                Line::from(""),
+                Line::from(format!("> {}", reference.value())),
+                Line::from(Span::styled(
+                    "e.g. my-image:debug, the tag defaults to latest",
+                    Style::new().dark_gray(),
+                )),
+                Line::from(vec![
+                    "ESC".bold(),
+                    " to Cancel, ".into(),
+                    "Enter".bold(),
+                    " to Confirm".into(),
+                ]),
+            ];
+            let paragraph = Paragraph::new(text).wrap(Wrap { trim: false });
+            let block = Block::default()
+                .title("Commit container".bold())
+                .padding(Padding::new(1, 1, 1, 1))
+                .borders(Borders::ALL);
+            let area = centered_rect(50, 10, f.size());
+            f.render_widget(Clear, area);
+            f.render_widget(paragraph.block(block), area);
+        }
+    }
+
+    pub(crate) fn handle_input(
+        &mut self,
+        kevent: event::KeyEvent,
+    ) -> Result<Option<event::KeyEvent>> {
+        match &mut self.commit {
+            Some(input) if kevent.kind == KeyEventKind::Press => match kevent.code {
+                KeyCode::Esc => {
+                    self.commit = None;
+                    Ok(None)
+                }
+                _ if input.handle_key(&kevent) => Ok(None),
+                _ => Ok(Some(kevent)),
+            },
+            Some(_) => Ok(None),
+            None => Ok(Some(kevent)),
+        }
+    }
+
+    pub(crate) fn handle_paste(&mut self, text: &str) {
+        if let Some(input) = &mut self.commit {
+            input.paste(text);
+        }
     }
 
     pub(crate) fn get_bindings(&self) -> Option<&[(&str, &str)]> {
-        Some(&[])
+        Some(&[
+            ("d", "Filesystem changes"),
+            ("m", "CPU/Memory stats"),
+            ("c", "Commit to a new image"),
+            ("x", "Show/hide masked environment values"),
+            ("I", "Copy the IP address"),
+            ("P", "Copy the first published port"),
+        ])
+    }
+
+    pub(crate) fn get_action(&self, k: &event::KeyEvent) -> Option<Action> {
+        match k.code {
+            // Ctrl-d is the global delete binding
+            KeyCode::Char('d') if k.modifiers == KeyModifiers::NONE => Some(Action::Changes),
+            KeyCode::Char('m') => Some(Action::Stats),
+            KeyCode::Char('c') => Some(Action::Commit),
+            KeyCode::Char('x') => Some(Action::Reveal),
+            KeyCode::Char('I') => Some(Action::CopyIp),
+            KeyCode::Char('P') => Some(Action::CopyPort),
+            _ => None,
+        }
     }
 
-    pub(crate) fn get_action(&self, _k: &event::KeyEvent) -> Option<Action> {
-        None
+    pub(crate) fn has_filter(&self) -> bool {
+        true
     }
 }
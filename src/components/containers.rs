@@ -5,10 +5,15 @@ use crossterm::event::{self, KeyCode, KeyEventKind};
 use futures::{executor::block_on, future::join_all, StreamExt};
 use humansize::{format_size, FormatSizeOptions, BINARY};
 
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use ratatui::{
-    layout::{Constraint, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Style, Stylize},
     text::{Line, Span},
     widgets::{Block, Borders, Cell, Clear, Padding, Paragraph, Row, TableState, Wrap},
@@ -16,50 +21,84 @@ use ratatui::{
 };
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
-use tokio::{select, spawn};
+use tokio::{select, spawn, try_join};
 use tokio::{sync::mpsc::UnboundedSender, time::sleep};
-use tokio_util::sync::CancellationToken;
+use tokio_util::sync::{CancellationToken, DropGuard};
 
-use crate::{action::Action, utils::centered_rect};
-use crate::{runtime::ContainerSummary, utils::table};
+use crate::{action::Action, config, utils::centered_rect};
+use crate::{
+    runtime::ContainerSummary,
+    utils::{
+        confirmation_popup, delete_selected, empty_state, format_rates, request_confirmation,
+        retain_listed, selection_style, table, table_page_size, toggle_selection, Age,
+    },
+};
 use crate::{
     runtime::{
         delete_container,
-        docker::{compute_cpu, compute_mem},
-        get_container, get_container_stats, list_containers, validate_container_filters,
-        ContainerMetrics, Filter,
+        docker::{compute_blkio, compute_cpu, compute_mem, compute_net, compute_read_time},
+        download_from_container, get_container, get_container_details, get_container_restart_count,
+        get_container_started_at, get_container_stats, is_remote, list_containers,
+        validate_container_filters, wait_container, ContainerHealth, ContainerMetrics,
+        ContainerStatus, Filter, NameFilter,
     },
     tui,
+    utils::get_data_dir,
 };
 
 use crate::components::{
     container_exec::ContainerExec, container_inspect::ContainerDetails,
-    container_logs::ContainerLogs, container_view::ContainerView, Component,
+    container_logs::ContainerLogs, container_view::ContainerView, input::TextInput,
+    inspect_diff::InspectDiff, Component,
 };
 
-const CONTAINER_CONSTRAINTS: [Constraint; 7] = [
-    Constraint::Percentage(20),
-    Constraint::Percentage(20),
-    Constraint::Percentage(20),
-    Constraint::Percentage(20),
+// Containers restarted at least this many times, and still restarting, are flagged
+const RESTART_LOOP_THRESHOLD: i64 = 3;
+// Delay after the last restart before a container is no longer flagged
+const RESTART_LOOP_WINDOW: Duration = Duration::from_secs(300);
+
+#[derive(Clone, Debug)]
+struct Restarts {
+    count: i64,
+    last_increase: Instant,
+}
+
+impl Restarts {
+    fn is_looping(&self) -> bool {
+        self.count >= RESTART_LOOP_THRESHOLD && self.last_increase.elapsed() < RESTART_LOOP_WINDOW
+    }
+}
+
+const CONTAINER_HEADERS: [&str; 9] = [
+    "Id", "Name", "Image", "Status", "Age", "CPU", "MEM", "NET", "BLOCK",
+];
+
+const CONTAINER_CONSTRAINTS: [Constraint; 9] = [
+    Constraint::Percentage(15),
+    Constraint::Percentage(15),
+    Constraint::Percentage(15),
+    Constraint::Percentage(15),
     Constraint::Max(4),
     Constraint::Max(5),
     Constraint::Max(9),
+    Constraint::Max(21),
+    Constraint::Max(21),
 ];
 
 #[derive(Clone, Debug)]
 enum Popup {
     None,
+    DeleteSelected,
     Delete(String, String),
     Shell(ShellPopup),
+    Download(ShellPopup),
 }
 
 #[derive(Clone, Debug, Default)]
 struct ShellPopup {
     cid: String,
     cname: String,
-    input: String,
-    cursor_position: usize,
+    input: TextInput,
 }
 
 impl ShellPopup {
@@ -85,30 +124,99 @@ pub enum SortColumn {
     Image(SortOrder),
     Status(SortOrder),
     Age(SortOrder),
+    Cpu(SortOrder),
+    Mem(SortOrder),
+}
+
+impl SortColumn {
+    // Header index of the sorted column and whether it is sorted in ascending order
+    fn indicator(&self) -> (usize, bool) {
+        let (column, order) = match self {
+            SortColumn::Id(o) => (0, o),
+            SortColumn::Name(o) => (1, o),
+            SortColumn::Image(o) => (2, o),
+            SortColumn::Status(o) => (3, o),
+            SortColumn::Age(o) => (4, o),
+            SortColumn::Cpu(o) => (5, o),
+            SortColumn::Mem(o) => (6, o),
+        };
+        (column, matches!(order, SortOrder::Asc))
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct Containers {
     all: bool,
+    // Only show the unhealthy, exited and dead containers
+    unhealthy: bool,
     state: TableState,
+    page_size: usize,
     containers: Vec<ContainerSummary>,
     show_popup: Popup,
+    selected: HashSet<String>,
     action_tx: Option<UnboundedSender<Action>>,
     sort_by: SortColumn,
     filter: Filter,
+    name_filter: Option<NameFilter>,
+    skipped_ticks: usize,
+    details_pane: bool,
+    details: Option<crate::runtime::ContainerDetails>,
+    // Restart counts of the containers seen restarting
+    restarts: HashMap<String, Restarts>,
+    // Start times of the running containers, fetched when `containers.uptime` is set, with the
+    // restart count known when they were fetched
+    started: HashMap<String, (Option<i64>, i64)>,
     metrics: Arc<Mutex<HashMap<String, ContainerMetrics>>>,
     task: Arc<JoinHandle<Result<()>>>,
     cancellation_token: CancellationToken,
+    // Cancels the task once the last clone of the component is dropped
+    _drop_guard: Arc<DropGuard>,
+}
+
+async fn wait(cid: String, cname: String, tx: UnboundedSender<Action>) -> Result<()> {
+    match wait_container(&cid).await {
+        Ok(code) => tx.send(Action::Info(format!(
+            "Container \"{}\" exited with code {}",
+            cname, code
+        )))?,
+        Err(e) => tx.send(Action::Error(format!(
+            "Unable to wait for container \"{}\":\n{}",
+            cname, e
+        )))?,
+    }
+    Ok(())
+}
+
+async fn download_file(
+    cid: String,
+    cname: String,
+    path: String,
+    tx: UnboundedSender<Action>,
+) -> Result<()> {
+    let dest = get_data_dir().join("downloads").join(&cname);
+    match download_from_container(&cid, &path, dest).await {
+        Ok(dest) => tx.send(Action::Info(format!(
+            "Copied \"{}\" to {}",
+            path,
+            dest.display()
+        )))?,
+        Err(e) => tx.send(Action::Error(format!(
+            "Unable to copy \"{}\" from container \"{}\":\n{}",
+            path, cname, e
+        )))?,
+    }
+    Ok(())
 }
 
 async fn run_setup_task(
     metrics: Arc<Mutex<HashMap<String, ContainerMetrics>>>,
     cancel: CancellationToken,
 ) -> Result<()> {
+    let interval = config::get().polling.metrics_interval(is_remote().await);
     let mut should_stop = false;
     while !should_stop {
         select!(
-        _ = update_metrics(Arc::clone(&metrics)) => {},
+        _ = update_metrics(Arc::clone(&metrics), interval) => {},
         _ = cancel.cancelled() => {
             should_stop = true;
         }
@@ -117,7 +225,10 @@ async fn run_setup_task(
     Ok(())
 }
 
-async fn update_metrics(metrics: Arc<Mutex<HashMap<String, ContainerMetrics>>>) -> Result<()> {
+async fn update_metrics(
+    metrics: Arc<Mutex<HashMap<String, ContainerMetrics>>>,
+    interval: Duration,
+) -> Result<()> {
     let container_list = list_containers(false, &Filter::default()).await?;
     let options = Some(StatsOptions {
         stream: false,
@@ -126,7 +237,7 @@ async fn update_metrics(metrics: Arc<Mutex<HashMap<String, ContainerMetrics>>>)
     let stats_futures = join_all(container_list.iter().map(|c| async {
         match get_container_stats(&c.id, options).await {
             Ok(mut stats) => match stats.next().await {
-                Some(Ok(stats)) => Some((c.id.clone(), compute_cpu(&stats), compute_mem(&stats))),
+                Some(Ok(stats)) => Some((c.id.clone(), stats)),
                 _ => None,
             },
             Err(_) => None,
@@ -136,24 +247,36 @@ async fn update_metrics(metrics: Arc<Mutex<HashMap<String, ContainerMetrics>>>)
 
     let mut map_lock = metrics.lock().await;
     for cid_stats in stats_futures.into_iter().filter(|s| s.is_some()) {
-        let (cid, cpu_usage, mem_usage) = cid_stats.expect("Already checked and filtered out None");
-        let entry = map_lock.get_mut(&cid);
-        match entry {
-            Some(entry) => entry.push_metrics(cpu_usage, mem_usage),
-            None => {
-                let mut cm = ContainerMetrics::new(cid.clone(), 20);
-                cm.push_metrics(cpu_usage, mem_usage);
-                map_lock.insert(cid, cm);
-            }
-        }
+        let (cid, stats) = cid_stats.expect("Already checked and filtered out None");
+        let entry = map_lock
+            .entry(cid.clone())
+            .or_insert_with(|| ContainerMetrics::new(cid, 20));
+        entry.push_metrics(compute_cpu(&stats), compute_mem(&stats));
+        entry.push_io(
+            compute_read_time(&stats),
+            compute_net(&stats),
+            compute_blkio(&stats),
+        );
     }
     drop(map_lock);
 
-    sleep(Duration::from_millis(1000)).await;
+    sleep(interval).await;
 
     Ok(())
 }
 
+// Indexes of the displayed columns, every one of them if the configuration hides them all
+fn visible_columns() -> Vec<usize> {
+    let columns: Vec<usize> = (0..CONTAINER_HEADERS.len())
+        .filter(|&i| config::get().containers.is_visible(CONTAINER_HEADERS[i]))
+        .collect();
+    if columns.is_empty() {
+        (0..CONTAINER_HEADERS.len()).collect()
+    } else {
+        columns
+    }
+}
+
 impl Containers {
     pub fn new(filter: Filter) -> Self {
         let metrics = Arc::new(Mutex::new(HashMap::new()));
@@ -166,14 +289,24 @@ impl Containers {
 
         Containers {
             all: false,
+            unhealthy: false,
             state: Default::default(),
+            page_size: 1,
             containers: Vec::new(),
             show_popup: Popup::None,
+            selected: HashSet::new(),
             action_tx: None,
             sort_by: SortColumn::Name(SortOrder::Asc),
             filter,
+            name_filter: None,
+            skipped_ticks: 0,
+            details_pane: false,
+            details: None,
+            restarts: HashMap::new(),
+            started: HashMap::new(),
             metrics,
             task: Arc::clone(&task),
+            _drop_guard: Arc::new(cancel.clone().drop_guard()),
             cancellation_token: cancel,
         }
     }
@@ -210,6 +343,152 @@ impl Containers {
         }
     }
 
+    fn prune_selected(&mut self) {
+        retain_listed(&mut self.selected, self.containers.iter().map(|c| &c.id));
+    }
+
+    // The restart count is only in the inspect, it's fetched for the containers seen restarting
+    async fn update_restarts(&mut self) {
+        let restarts = &mut self.restarts;
+        let watched: Vec<String> = self
+            .containers
+            .iter()
+            .filter(|c| {
+                matches!(c.status, ContainerStatus::Restarting) || restarts.contains_key(&c.id)
+            })
+            .map(|c| c.id.clone())
+            .collect();
+        restarts.retain(|id, _| watched.contains(id));
+        let running: HashSet<&String> = self
+            .containers
+            .iter()
+            .filter(|c| matches!(c.status, ContainerStatus::Running(_)))
+            .map(|c| &c.id)
+            .collect();
+        let counts = join_all(watched.into_iter().map(|id| async {
+            let count = get_container_restart_count(&id).await;
+            (id, count)
+        }))
+        .await;
+        for (id, count) in counts {
+            let Ok(count) = count else { continue };
+            match restarts.get_mut(&id) {
+                Some(r) if count > r.count => {
+                    r.count = count;
+                    r.last_increase = Instant::now();
+                }
+                // Back to running without restarting again, it's not watched anymore
+                Some(_) if running.contains(&id) => {
+                    restarts.remove(&id);
+                }
+                Some(_) => {}
+                None => {
+                    restarts.insert(
+                        id,
+                        Restarts {
+                            count,
+                            last_increase: Instant::now(),
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    // Docker's `health` filter only matches running containers, this one is applied client side
+    fn apply_unhealthy_filter(&mut self) {
+        if self.unhealthy {
+            self.containers.retain(|c| {
+                matches!(
+                    c.status,
+                    ContainerStatus::Running(ContainerHealth::Unhealthy)
+                        | ContainerStatus::Exited
+                        | ContainerStatus::Dead
+                )
+            });
+        }
+    }
+
+    // Dropped once a container leaves the running state or its restart count changes, so it's
+    // fetched again after a restart
+    async fn update_started(&mut self) {
+        let running: Vec<&ContainerSummary> = self
+            .containers
+            .iter()
+            .filter(|c| matches!(c.status, ContainerStatus::Running(_)))
+            .collect();
+        let restarts = &self.restarts;
+        let restart_count = |id: &String| restarts.get(id).map(|r| r.count);
+        self.started.retain(|id, (count, _)| {
+            running.iter().any(|c| &c.id == id) && *count == restart_count(id)
+        });
+        let missing = running
+            .iter()
+            .filter(|c| !self.started.contains_key(&c.id))
+            .map(|c| async {
+                let started = get_container_started_at(&c.id).await;
+                (c.id.clone(), restart_count(&c.id), started)
+            });
+        for (id, count, started) in join_all(missing).await {
+            if let Ok(Some(started)) = started {
+                self.started.insert(id, (count, started));
+            }
+        }
+        for c in self.containers.iter_mut() {
+            c.started = self.started.get(&c.id).map(|(_, started)| *started);
+        }
+    }
+
+    fn apply_name_filter(&mut self) {
+        if let Some(f) = &self.name_filter {
+            self.containers.retain(|c| match f {
+                NameFilter::Fuzzy(_) => f.matches(&c.name) || f.matches(&c.image),
+                NameFilter::Regex(_) => f.matches(&c.name),
+            });
+        }
+    }
+
+    fn toggle_selected(&mut self) {
+        let id = self
+            .state
+            .selected()
+            .and_then(|i| self.containers.get(i))
+            .map(|c| &c.id);
+        if toggle_selection(&mut self.selected, id) {
+            self.page_down(1);
+        }
+    }
+
+    fn first(&mut self) {
+        if !self.containers.is_empty() {
+            self.state.select(Some(0));
+        }
+    }
+
+    fn last(&mut self) {
+        if !self.containers.is_empty() {
+            self.state.select(Some(self.containers.len() - 1));
+        }
+    }
+
+    fn page_up(&mut self, qty: usize) {
+        if !self.containers.is_empty() {
+            let i = self.state.selected().unwrap_or(0).saturating_sub(qty);
+            self.state.select(Some(i));
+        }
+    }
+
+    fn page_down(&mut self, qty: usize) {
+        if !self.containers.is_empty() {
+            let i = self
+                .state
+                .selected()
+                .map_or(0, |i| i.saturating_add(qty))
+                .min(self.containers.len() - 1);
+            self.state.select(Some(i));
+        }
+    }
+
     fn get_selected_container_info(&self) -> Option<(String, String)> {
         self.state
             .selected()
@@ -218,6 +497,15 @@ impl Containers {
     }
 
     fn draw_popup(&self, f: &mut Frame<'_>) {
+        if let Popup::DeleteSelected = &self.show_popup {
+            confirmation_popup(
+                f,
+                Line::from(format!(
+                    "Are you sure you want to delete {} containers?",
+                    self.selected.len()
+                )),
+            );
+        }
         match &self.show_popup {
             Popup::Delete(_cid, cname) => {
                 let text = vec![
@@ -250,8 +538,11 @@ impl Containers {
                         "You will launch the following command in the container:",
                     )]),
                     Line::from(""),
-                    Line::from(format!("> {}", shell_popup.input.clone())),
-                    Line::from(""),
+                    Line::from(format!("> {}", shell_popup.input.value())),
+                    Line::from(Span::styled(
+                        "Optional prefix: [user]@[workdir] KEY=VALUE..., e.g. root@/tmp TERM=xterm",
+                        Style::new().dark_gray(),
+                    )),
                     Line::from(vec![
                         "ESC".bold(),
                         " to Cancel, ".into(),
@@ -269,69 +560,61 @@ impl Containers {
                 f.render_widget(Clear, area); //this clears out the background
                 f.render_widget(paragraph.block(block), area);
             }
-            _ => {}
-        }
-    }
-
-    fn delete_char(&mut self) {
-        if let Popup::Shell(ref mut shell_popup) = self.show_popup {
-            let is_not_cursor_leftmost = shell_popup.cursor_position != 0;
-            if is_not_cursor_leftmost {
-                // Method "remove" is not used on the saved text for deleting the selected char.
-                // Reason: Using remove on String works on bytes instead of the chars.
-                // Using remove would require special care because of char boundaries.
-
-                let current_index = shell_popup.cursor_position;
-                let from_left_to_current_index = current_index - 1;
-
-                // Getting all characters before the selected character.
-                let before_char_to_delete =
-                    shell_popup.input.chars().take(from_left_to_current_index);
-                // Getting all characters after selected character.
-                let after_char_to_delete = shell_popup.input.chars().skip(current_index);
+            Popup::Download(download_popup) => {
+                let text = vec![
+                    Line::from(vec![
+                        Span::raw("Path of the file or directory to copy from \""),
+                        Span::styled(&download_popup.cname, Style::new().gray()),
+                        Span::raw("\":"),
+                    ]),
+                    Line::from(""),
+                    Line::from(format!("> {}", download_popup.input.value())),
+                    Line::from(""),
+                    Line::from(vec![
+                        "ESC".bold(),
+                        " to Cancel, ".into(),
+                        "Enter".bold(),
+                        " to Confirm".into(),
+                    ]),
+                ];
+                let paragraph = Paragraph::new(text).wrap(Wrap { trim: false });
 
-                // Put all characters together except the selected one.
-                // By leaving the selected one out, it is forgotten and therefore deleted.
-                shell_popup.input = before_char_to_delete.chain(after_char_to_delete).collect();
-                self.move_cursor_left();
+                let block = Block::default()
+                    .title("Copy from container".bold())
+                    .padding(Padding::new(1, 1, 1, 1))
+                    .borders(Borders::ALL);
+                let area = centered_rect(50, 10, f.size());
+                f.render_widget(Clear, area); //this clears out the background
+                f.render_widget(paragraph.block(block), area);
             }
+            _ => {}
         }
     }
 
-    fn enter_char(&mut self, new_char: char) {
-        if let Popup::Shell(ref mut shell_popup) = self.show_popup {
-            shell_popup
-                .input
-                .insert(shell_popup.cursor_position, new_char);
-
-            self.move_cursor_right();
-        }
-    }
-
-    fn move_cursor_left(&mut self) {
-        if let Popup::Shell(ref mut shell_popup) = self.show_popup {
-            let cursor_moved_left = shell_popup.cursor_position.saturating_sub(1);
-            let length = shell_popup.input.len();
-            shell_popup.cursor_position = cursor_moved_left.clamp(0, length);
-        }
-    }
-
-    fn move_cursor_right(&mut self) {
-        if let Popup::Shell(ref mut shell_popup) = self.show_popup {
-            let cursor_moved_right = shell_popup.cursor_position.saturating_add(1);
-            let length = shell_popup.input.len();
-            shell_popup.cursor_position = cursor_moved_right.clamp(0, length);
-        }
-    }
-
-    fn sort(&mut self) {
+    fn sort(&mut self, metrics: &HashMap<String, ContainerMetrics>) {
+        let cpu = |cid: &str| metrics.get(cid).and_then(|m| m.cpu_data().next().copied());
+        let mem = |cid: &str| metrics.get(cid).and_then(|m| m.mem_data().next().copied());
+        let uptime = config::get().containers.uptime;
         self.containers.sort_by(|a, b| {
             let (cmp_result, o) = match &self.sort_by {
                 SortColumn::Id(o) => (a.id.cmp(&b.id), o),
                 SortColumn::Name(o) => (a.name.cmp(&b.name), o),
                 SortColumn::Image(o) => (a.image.cmp(&b.image), o),
                 SortColumn::Status(o) => (a.status.cmp(&b.status), o),
-                SortColumn::Age(o) => (a.age.cmp(&b.age), o),
+                SortColumn::Age(o) => (a.since(uptime).cmp(&b.since(uptime)), o),
+                // Containers without metrics are always sorted last
+                SortColumn::Cpu(o) => match (cpu(&a.id), cpu(&b.id)) {
+                    (Some(a), Some(b)) => (a.total_cmp(&b), o),
+                    (Some(_), None) => return Ordering::Less,
+                    (None, Some(_)) => return Ordering::Greater,
+                    (None, None) => (Ordering::Equal, o),
+                },
+                SortColumn::Mem(o) => match (mem(&a.id), mem(&b.id)) {
+                    (Some(a), Some(b)) => (a.cmp(&b), o),
+                    (Some(_), None) => return Ordering::Less,
+                    (None, Some(_)) => return Ordering::Greater,
+                    (None, None) => (Ordering::Equal, o),
+                },
             };
             match o {
                 SortOrder::Asc => cmp_result,
@@ -353,10 +636,24 @@ impl Containers {
             .action_tx
             .clone()
             .expect("Action tx queue not initialized");
+        let tick = matches!(action, Action::Tick);
         match (action, self.show_popup.clone()) {
+            (Action::Refresh, Popup::None) => {
+                // The following tick is not skipped
+                self.skipped_ticks = 0;
+            }
+            (Action::Tick, Popup::None) if self.skipped_ticks > 0 => {
+                self.skipped_ticks -= 1;
+            }
             (Action::Tick, Popup::None) => {
-                self.containers = match list_containers(self.all, &self.filter).await {
-                    Ok(containers) => containers,
+                self.skipped_ticks = config::get().list_skipped_ticks(is_remote().await);
+                // Exited and dead containers are only listed with `all`
+                let all = self.all || self.unhealthy;
+                self.containers = match list_containers(all, &self.filter).await {
+                    Ok(containers) => {
+                        tx.send(Action::Refreshed)?;
+                        containers
+                    }
                     Err(e) => {
                         tx.send(Action::Error(format!(
                             "Error getting container list: {}",
@@ -365,7 +662,15 @@ impl Containers {
                         vec![]
                     }
                 };
-                self.sort();
+                self.apply_unhealthy_filter();
+                self.apply_name_filter();
+                self.prune_selected();
+                self.update_restarts().await;
+                if config::get().containers.uptime {
+                    self.update_started().await;
+                }
+                let metrics = Arc::clone(&self.metrics);
+                self.sort(&*metrics.lock().await);
                 if self.state.selected().is_none() {
                     self.state.select(Some(0));
                 }
@@ -376,9 +681,24 @@ impl Containers {
             (Action::Up, Popup::None) => {
                 self.previous();
             }
+            (Action::Top, Popup::None) => {
+                self.first();
+            }
+            (Action::Bottom, Popup::None) => {
+                self.last();
+            }
+            (Action::PageUp, Popup::None) => {
+                self.page_up(self.page_size);
+            }
+            (Action::PageDown, Popup::None) => {
+                self.page_down(self.page_size);
+            }
             (Action::All, Popup::None) => {
                 self.all = !self.all;
             }
+            (Action::Unhealthy, Popup::None) => {
+                self.unhealthy = !self.unhealthy;
+            }
             (Action::SetFilter(filter), Popup::None) => {
                 if let Some(filter) = filter {
                     if validate_container_filters(&filter).await {
@@ -389,6 +709,11 @@ impl Containers {
                 } else {
                     self.filter = Default::default();
                 }
+                self.name_filter = None;
+            }
+            (Action::SetNameFilter(name_filter), Popup::None) => {
+                self.name_filter = name_filter;
+                self.apply_name_filter();
             }
             (Action::Inspect, Popup::None) => {
                 if let Some(cinfo) = self.get_selected_container_info() {
@@ -406,6 +731,34 @@ impl Containers {
                     tx.send(action)?;
                 };
             }
+            (Action::Compare, Popup::None) => {
+                let selected: Vec<(String, String)> = self
+                    .containers
+                    .iter()
+                    .filter(|c| self.selected.contains(&c.id))
+                    .map(|c| (c.id.to_string(), c.name.to_string()))
+                    .collect();
+                let action = match &selected[..] {
+                    [(lcid, lname), (rcid, rname)] => {
+                        match try_join!(get_container(lcid), get_container(rcid)) {
+                            Ok((ldetails, rdetails)) => {
+                                Action::Screen(Component::InspectDiff(InspectDiff::new(
+                                    (lcid.to_string(), lname.to_string(), ldetails),
+                                    (rcid.to_string(), rname.to_string(), rdetails),
+                                )))
+                            }
+                            Err(e) => Action::Error(format!(
+                                "Unable to get containers \"{}\" and \"{}\" details:\n{}",
+                                lname, rname, e
+                            )),
+                        }
+                    }
+                    _ => Action::Error(
+                        "Select 2 containers with 'Space' to compare them".to_string(),
+                    ),
+                };
+                tx.send(action)?;
+            }
             (Action::Logs, Popup::None) => {
                 if let Some(cinfo) = self.get_selected_container_info() {
                     let cid = cinfo.0.to_string();
@@ -418,9 +771,9 @@ impl Containers {
             (Action::Ok, Popup::None) => {
                 if let Some((cid, _)) = self.get_selected_container_info() {
                     let cid = cid.to_string();
-                    tx.send(Action::Screen(Component::ContainerView(
+                    tx.send(Action::Screen(Component::ContainerView(Box::new(
                         ContainerView::new(cid),
-                    )))?;
+                    ))))?;
                 }
             }
             (Action::Shell, Popup::None) => {
@@ -438,11 +791,42 @@ impl Containers {
                     self.show_popup = Popup::Shell(ShellPopup::new(cid, cname));
                 }
             }
+            (Action::Download, Popup::None) => {
+                if let Some((cid, cname)) = self.get_selected_container_info() {
+                    self.show_popup = Popup::Download(ShellPopup::new(cid, cname));
+                }
+            }
+            (Action::Select, Popup::None) => {
+                self.toggle_selected();
+            }
+            (Action::Wait, Popup::None) => {
+                if let Some((cid, cname)) = self.get_selected_container_info() {
+                    tx.send(Action::Info(format!(
+                        "Waiting for container \"{}\" to exit",
+                        cname
+                    )))?;
+                    spawn(wait(cid, cname, tx.clone()));
+                }
+            }
+            (Action::Delete, Popup::None) if !self.selected.is_empty() => {
+                self.show_popup = Popup::DeleteSelected;
+                request_confirmation(&tx)?;
+            }
             (Action::Delete, Popup::None) => {
                 if let Some((cid, cname)) = self.get_selected_container_info() {
                     self.show_popup = Popup::Delete(cid, cname);
+                    request_confirmation(&tx)?;
                 }
             }
+            (Action::Ok, Popup::DeleteSelected) => {
+                spawn(delete_selected(
+                    "containers",
+                    self.selected.drain().collect(),
+                    |id| async move { delete_container(&id).await },
+                    tx.clone(),
+                ));
+                self.show_popup = Popup::None;
+            }
             (Action::Ok, Popup::Delete(cid, _)) => {
                 if let Err(e) = delete_container(&cid).await {
                     tx.send(Action::Error(format!(
@@ -455,55 +839,138 @@ impl Containers {
                 }
             }
             (Action::Ok, Popup::Shell(shell)) => {
+                self.show_popup = Popup::None;
                 let action = Action::Screen(Component::ContainerExec(ContainerExec::new(
                     shell.cid,
                     shell.cname,
-                    Some(shell.input),
+                    Some(shell.input.value().into()),
                 )));
                 tx.send(Action::Suspend)?;
                 tx.send(action)?;
             }
+            (Action::Ok, Popup::Download(download)) => {
+                let path = download.input.value().to_string();
+                tx.send(Action::Info(format!("Copying \"{}\"...", path)))?;
+                spawn(download_file(
+                    download.cid,
+                    download.cname,
+                    path,
+                    tx.clone(),
+                ));
+                self.show_popup = Popup::None;
+            }
             (Action::PreviousScreen, Popup::Delete(_, _))
-            | (Action::PreviousScreen, Popup::Shell(_)) => {
+            | (Action::PreviousScreen, Popup::DeleteSelected)
+            | (Action::PreviousScreen, Popup::Shell(_))
+            | (Action::PreviousScreen, Popup::Download(_)) => {
                 self.show_popup = Popup::None;
             }
             (Action::SortColumn(n), Popup::None) => {
-                self.sort_by = match (n, &self.sort_by) {
-                    (1, SortColumn::Id(SortOrder::Asc)) => SortColumn::Id(SortOrder::Desc),
-                    (1, _) => SortColumn::Id(SortOrder::Asc),
-                    (2, SortColumn::Name(SortOrder::Asc)) => SortColumn::Name(SortOrder::Desc),
-                    (2, _) => SortColumn::Name(SortOrder::Asc),
-                    (3, SortColumn::Image(SortOrder::Asc)) => SortColumn::Image(SortOrder::Desc),
-                    (3, _) => SortColumn::Image(SortOrder::Asc),
-                    (4, SortColumn::Status(SortOrder::Asc)) => SortColumn::Status(SortOrder::Desc),
-                    (4, _) => SortColumn::Status(SortOrder::Asc),
-                    (5, SortColumn::Age(SortOrder::Asc)) => SortColumn::Age(SortOrder::Desc),
-                    (5, _) => SortColumn::Age(SortOrder::Asc),
+                // `F<n>` sorts by the n-th displayed column
+                let column = (n as usize)
+                    .checked_sub(1)
+                    .and_then(|n| visible_columns().get(n).copied());
+                self.sort_by = match (column, &self.sort_by) {
+                    (Some(0), SortColumn::Id(SortOrder::Asc)) => SortColumn::Id(SortOrder::Desc),
+                    (Some(0), _) => SortColumn::Id(SortOrder::Asc),
+                    (Some(1), SortColumn::Name(SortOrder::Asc)) => {
+                        SortColumn::Name(SortOrder::Desc)
+                    }
+                    (Some(1), _) => SortColumn::Name(SortOrder::Asc),
+                    (Some(2), SortColumn::Image(SortOrder::Asc)) => {
+                        SortColumn::Image(SortOrder::Desc)
+                    }
+                    (Some(2), _) => SortColumn::Image(SortOrder::Asc),
+                    (Some(3), SortColumn::Status(SortOrder::Asc)) => {
+                        SortColumn::Status(SortOrder::Desc)
+                    }
+                    (Some(3), _) => SortColumn::Status(SortOrder::Asc),
+                    (Some(4), SortColumn::Age(SortOrder::Asc)) => SortColumn::Age(SortOrder::Desc),
+                    (Some(4), _) => SortColumn::Age(SortOrder::Asc),
+                    (Some(5), SortColumn::Cpu(SortOrder::Asc)) => SortColumn::Cpu(SortOrder::Desc),
+                    (Some(5), _) => SortColumn::Cpu(SortOrder::Asc),
+                    (Some(6), SortColumn::Mem(SortOrder::Asc)) => SortColumn::Mem(SortOrder::Desc),
+                    (Some(6), _) => SortColumn::Mem(SortOrder::Asc),
                     _ => self.sort_by.clone(),
                 }
             }
+            (Action::DetailsPane, Popup::None) => {
+                self.details_pane = !self.details_pane;
+                self.details = None;
+            }
             _ => {}
         }
+        if self.details_pane {
+            self.update_details(tick).await;
+        }
         Ok(())
     }
 
+    // Fetches the details of the selected container when the selection changed,
+    // or on every tick to keep the pane live
+    async fn update_details(&mut self, force: bool) {
+        let selected = self.get_selected_container_info().map(|(cid, _)| cid);
+        let current = self.details.as_ref().map(|d| d.id.clone());
+        if force || selected != current {
+            self.details = match selected {
+                Some(cid) => get_container_details(&cid).await.ok(),
+                None => None,
+            };
+        }
+    }
+
     pub(crate) fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
         let stats = block_on(async { self.metrics.lock().await.clone() });
-        let rects = Layout::default()
-            .constraints([Constraint::Percentage(100)])
-            .split(area);
+        let rects = if self.details_pane {
+            Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(67), Constraint::Percentage(33)])
+                .split(area)
+        } else {
+            Layout::default()
+                .constraints([Constraint::Percentage(100)])
+                .split(area)
+        };
+        let columns = visible_columns();
+        let headers: Vec<&str> = columns.iter().map(|&i| CONTAINER_HEADERS[i]).collect();
+        let constraints: Vec<Constraint> =
+            columns.iter().map(|&i| CONTAINER_CONSTRAINTS[i]).collect();
+        let (sort_column, ascending) = self.sort_by.indicator();
+        let sorted = columns
+            .iter()
+            .position(|&i| i == sort_column)
+            .map(|position| (position, ascending));
         let t = table(
             format!(
-                "{} ({}{})",
+                "{} ({}{}{})",
                 self.get_name(),
-                if self.all { "All" } else { "Running" },
-                self.filter.format()
+                if self.unhealthy {
+                    "Unhealthy/Exited/Dead"
+                } else if self.all {
+                    "All"
+                } else {
+                    "Running"
+                },
+                self.filter.format(),
+                self.name_filter
+                    .as_ref()
+                    .map(NameFilter::format)
+                    .unwrap_or_default()
             ),
-            ["Id", "Name", "Image", "Status", "Age", "CPU", "MEM"],
+            &headers,
             self.containers
                 .iter()
                 .map(|c| {
                     let mut cells: Vec<Cell> = c.into();
+                    if config::get().containers.uptime {
+                        cells[4] = c.since(true).age().gray().into();
+                    }
+                    if let Some(r) = self.restarts.get(&c.id).filter(|r| r.is_looping()) {
+                        cells[3] = Cell::new(Line::from(vec![
+                            c.status.format(),
+                            Span::styled(format!(" ⟳{}", r.count), Style::new().red().bold()),
+                        ]));
+                    }
                     if let Some(stats) = stats.get(&c.id) {
                         if let Some(cpu) = stats.cpu_data().next() {
                             cells.push(Cell::new(format!("{:.1}%", cpu)));
@@ -516,17 +983,65 @@ impl Containers {
                         } else {
                             cells.push(Cell::new("-".to_string()));
                         }
+                        if let Some((rx, tx)) = stats.net_data().next() {
+                            cells.push(Cell::new(format_rates(*rx, *tx)));
+                        } else {
+                            cells.push(Cell::new("-".to_string()));
+                        }
+                        if let Some((read, write)) = stats.blkio_data().next() {
+                            cells.push(Cell::new(format_rates(*read, *write)));
+                        } else {
+                            cells.push(Cell::new("-".to_string()));
+                        }
                     } else {
                         cells.push(Cell::new("-".to_string()));
                         cells.push(Cell::new("-".to_string()));
+                        cells.push(Cell::new("-".to_string()));
+                        cells.push(Cell::new("-".to_string()));
                     }
-                    Row::new(cells)
+                    let row = Row::new(
+                        cells
+                            .into_iter()
+                            .enumerate()
+                            .filter(|(i, _)| columns.contains(i))
+                            .map(|(_, cell)| cell),
+                    );
+                    selection_style(row, self.selected.contains(&c.id))
                 })
                 .collect(),
-            &CONTAINER_CONSTRAINTS,
+            &constraints,
             Some(Style::new().gray()),
+            sorted,
         );
+        self.page_size = table_page_size(rects[0]);
         f.render_stateful_widget(t, rects[0], &mut self.state);
+        if self.containers.is_empty() {
+            empty_state(
+                f,
+                rects[0],
+                "containers",
+                &[
+                    self.filter.to_string(),
+                    self.name_filter
+                        .as_ref()
+                        .map(|f| f.to_string())
+                        .unwrap_or_default(),
+                ],
+            );
+        }
+
+        if self.details_pane {
+            let lines: Vec<Line> = match &self.details {
+                Some(details) => (&details.masked()).into(),
+                None => vec![Line::from("No container selected".italic())],
+            };
+            let pane = Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(Span::styled("Details", Style::default().bold())),
+            );
+            f.render_widget(pane, rects[1]);
+        }
 
         self.draw_popup(f);
     }
@@ -535,29 +1050,16 @@ impl Containers {
         &mut self,
         kevent: event::KeyEvent,
     ) -> Result<Option<event::KeyEvent>> {
-        if let Popup::Shell(ref mut _shell_popup) = self.show_popup {
+        if let Popup::Shell(ref mut shell_popup) | Popup::Download(ref mut shell_popup) =
+            self.show_popup
+        {
             if kevent.kind == KeyEventKind::Press {
                 match kevent.code {
-                    KeyCode::Char(to_insert) => {
-                        self.enter_char(to_insert);
-                        Ok(None)
-                    }
-                    KeyCode::Backspace => {
-                        self.delete_char();
-                        Ok(None)
-                    }
-                    KeyCode::Left => {
-                        self.move_cursor_left();
-                        Ok(None)
-                    }
-                    KeyCode::Right => {
-                        self.move_cursor_right();
-                        Ok(None)
-                    }
                     KeyCode::Esc => {
                         self.show_popup = Popup::None;
                         Ok(None)
                     }
+                    _ if shell_popup.input.handle_key(&kevent) => Ok(None),
                     _ => Ok(Some(kevent)),
                 }
             } else {
@@ -568,12 +1070,35 @@ impl Containers {
         }
     }
 
+    pub(crate) fn handle_paste(&mut self, text: &str) {
+        if let Popup::Shell(ref mut shell_popup) | Popup::Download(ref mut shell_popup) =
+            self.show_popup
+        {
+            shell_popup.input.paste(text);
+        }
+    }
+
     fn cancel(&mut self) -> Result<()> {
         self.cancellation_token.cancel();
         self.task.abort();
         Ok(())
     }
 
+    pub(crate) fn pause(&mut self) -> Result<()> {
+        self.cancel()
+    }
+
+    pub(crate) fn resume(&mut self) -> Result<()> {
+        let cancel = CancellationToken::new();
+        self.task = Arc::new(spawn(run_setup_task(
+            Arc::clone(&self.metrics),
+            cancel.clone(),
+        )));
+        self._drop_guard = Arc::new(cancel.clone().drop_guard());
+        self.cancellation_token = cancel;
+        Ok(())
+    }
+
     pub(crate) fn teardown(&mut self, _t: &mut tui::Tui) -> Result<()> {
         self.cancel()?;
         Ok(())
@@ -583,23 +1108,38 @@ impl Containers {
         Some(&[
             ("Enter", "Container view"),
             ("ctrl+d", "Delete"),
+            ("Space", "Select/Unselect"),
             ("i", "Inspect"),
             ("l", "Logs"),
             ("s", "Execute '/bin/bash' in container"),
             ("S", "Execute custom command"),
+            ("c", "Copy file from container"),
+            ("w", "Wait for the container to exit"),
+            ("p", "Toggle the details pane"),
+            ("u", "Only unhealthy/exited/dead containers"),
+            ("D", "Diff the inspect of the 2 selected containers"),
             ("F1", "Sort by container id"),
             ("F2", "Sort by container name"),
             ("F3", "Sort by image name"),
             ("F4", "Sort by status"),
+            ("F5", "Sort by age"),
+            ("F6", "Sort by CPU usage"),
+            ("F7", "Sort by memory usage"),
         ])
     }
 
     pub(crate) fn get_action(&self, k: &event::KeyEvent) -> Option<Action> {
         match k.code {
             KeyCode::Char('i') => Some(Action::Inspect),
+            KeyCode::Char(' ') => Some(Action::Select),
             KeyCode::Char('l') => Some(Action::Logs),
             KeyCode::Char('s') => Some(Action::Shell),
             KeyCode::Char('S') => Some(Action::CustomShell),
+            KeyCode::Char('c') => Some(Action::Download),
+            KeyCode::Char('w') => Some(Action::Wait),
+            KeyCode::Char('p') => Some(Action::DetailsPane),
+            KeyCode::Char('u') => Some(Action::Unhealthy),
+            KeyCode::Char('D') => Some(Action::Compare),
             KeyCode::Enter => Some(Action::Ok),
             _ => None,
         }
@@ -609,3 +1149,45 @@ impl Containers {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn finished(task: &JoinHandle<Result<()>>) -> bool {
+        for _ in 0..100 {
+            if task.is_finished() {
+                return true;
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+        false
+    }
+
+    #[tokio::test]
+    async fn metrics_task_is_stopped_when_paused() {
+        let mut containers = Containers::new(Default::default());
+        let task = Arc::clone(&containers.task);
+        assert!(!task.is_finished());
+        containers.pause().unwrap();
+        assert!(finished(&task).await);
+
+        containers.resume().unwrap();
+        let task = Arc::clone(&containers.task);
+        assert!(!task.is_finished());
+        containers.cancel().unwrap();
+        assert!(finished(&task).await);
+    }
+
+    #[tokio::test]
+    async fn metrics_task_is_stopped_when_dropped() {
+        let containers = Containers::new(Default::default());
+        let task = Arc::clone(&containers.task);
+        let copy = containers.clone();
+        drop(containers);
+        // Still used by the copy
+        assert!(!finished(&task).await);
+        drop(copy);
+        assert!(finished(&task).await);
+    }
+}
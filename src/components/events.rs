@@ -0,0 +1,242 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use color_eyre::Result;
+use crossterm::event::{self, KeyCode};
+use futures::{executor::block_on, StreamExt};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph},
+};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio::{select, spawn};
+use tokio_util::sync::{CancellationToken, DropGuard};
+
+use crate::action::Action;
+use crate::components::Fallback;
+use crate::runtime::{get_events, Event};
+use crate::tui;
+
+// Number of events kept in memory, the oldest ones are dropped
+const EVENTS_CAPACITY: usize = 1_000;
+
+#[derive(Clone, Debug)]
+pub struct Events {
+    events: Arc<Mutex<VecDeque<Event>>>,
+    // Set when the events stream failed or ended
+    disconnected: Arc<AtomicBool>,
+    task: Option<Arc<JoinHandle<Result<()>>>>,
+    cancellation_token: CancellationToken,
+    _drop_guard: Arc<DropGuard>,
+    vertical_scroll: usize,
+    auto_scroll: bool,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+async fn stream_events(
+    events: Arc<Mutex<VecDeque<Event>>>,
+    disconnected: Arc<AtomicBool>,
+    tx: UnboundedSender<Action>,
+    cancel: CancellationToken,
+) -> Result<()> {
+    let mut should_stop = false;
+    let mut stream = match get_events().await {
+        Ok(stream) => stream,
+        Err(e) => {
+            disconnected.store(true, Ordering::Relaxed);
+            tx.send(Action::Error(format!("Unable to get events:\n{}", e)))?;
+            return Ok(());
+        }
+    };
+    while !should_stop {
+        select!(
+        e = stream.next() => {
+            match e {
+                Some(Ok(event)) => {
+                    let mut events = events.lock().await;
+                    if events.len() >= EVENTS_CAPACITY {
+                        events.pop_front();
+                    }
+                    events.push_back(event);
+                }
+                Some(Err(e)) => {
+                    disconnected.store(true, Ordering::Relaxed);
+                    should_stop = true;
+                    tx.send(Action::Error(format!("Events stream error:\n{}", e)))?;
+                }
+                None => {
+                    disconnected.store(true, Ordering::Relaxed);
+                    should_stop = true;
+                }
+            }
+        }
+        _ = cancel.cancelled() => {
+            should_stop = true;
+        }
+        );
+    }
+    Ok(())
+}
+
+impl Events {
+    pub fn new() -> Self {
+        let events = Arc::new(Mutex::new(VecDeque::new()));
+        let cancel = CancellationToken::new();
+
+        // The events task is started once the action sender is registered
+        Events {
+            events,
+            disconnected: Arc::new(AtomicBool::new(false)),
+            task: None,
+            _drop_guard: Arc::new(cancel.clone().drop_guard()),
+            cancellation_token: cancel,
+            vertical_scroll: 0,
+            auto_scroll: true,
+            action_tx: None,
+        }
+    }
+
+    fn start(&mut self) {
+        let tx = self.action_tx.clone().expect("No action sender");
+        let cancel = CancellationToken::new();
+
+        self.disconnected.store(false, Ordering::Relaxed);
+        let task = Arc::new(spawn(stream_events(
+            Arc::clone(&self.events),
+            Arc::clone(&self.disconnected),
+            tx,
+            cancel.clone(),
+        )));
+
+        self.task = Some(task);
+        self._drop_guard = Arc::new(cancel.clone().drop_guard());
+        self.cancellation_token = cancel;
+    }
+
+    fn cancel(&mut self) -> Result<()> {
+        self.cancellation_token.cancel();
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+        Ok(())
+    }
+
+    fn down(&mut self, qty: usize) {
+        self.vertical_scroll = self.vertical_scroll.saturating_add(qty);
+    }
+
+    fn up(&mut self, qty: usize) {
+        self.auto_scroll = false;
+        self.vertical_scroll = self.vertical_scroll.saturating_sub(qty);
+    }
+
+    pub(crate) fn get_name(&self) -> &'static str {
+        "Events"
+    }
+
+    pub(crate) fn register_action_handler(&mut self, action_tx: UnboundedSender<Action>) {
+        self.action_tx = Some(action_tx);
+        self.start();
+    }
+
+    pub(crate) async fn update(&mut self, action: Action) -> Result<()> {
+        let tx = self.action_tx.clone().expect("No action sender");
+        match action {
+            Action::PreviousScreen => {
+                self.cancel()?;
+                tx.send(Action::Back(Fallback::Containers))?;
+            }
+            Action::Up => {
+                self.up(1);
+            }
+            Action::Down => {
+                self.down(1);
+            }
+            Action::PageUp => {
+                self.up(15);
+            }
+            Action::PageDown => {
+                self.down(15);
+            }
+            Action::Top => {
+                self.auto_scroll = false;
+                self.vertical_scroll = 0;
+            }
+            Action::Bottom => {
+                self.auto_scroll = true;
+            }
+            Action::AutoScroll => {
+                self.auto_scroll = !self.auto_scroll;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub(crate) fn pause(&mut self) -> Result<()> {
+        self.cancel()
+    }
+
+    pub(crate) fn resume(&mut self) -> Result<()> {
+        self.start();
+        Ok(())
+    }
+
+    pub(crate) fn teardown(&mut self, _t: &mut tui::Tui) -> Result<()> {
+        self.cancel()?;
+        Ok(())
+    }
+
+    pub(crate) fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let events = block_on(self.events.lock());
+        let height = usize::from(area.height.saturating_sub(2));
+        let bottom = events.len().saturating_sub(height);
+        if self.auto_scroll || self.vertical_scroll > bottom {
+            self.vertical_scroll = bottom;
+        }
+        let text: Vec<Line> = if events.is_empty() {
+            vec![Line::from("Waiting for events...")]
+        } else {
+            events
+                .iter()
+                .skip(self.vertical_scroll)
+                .take(height)
+                .map(Line::from)
+                .collect()
+        };
+        let paragraph =
+            Paragraph::new(text).block(Block::default().borders(Borders::ALL).gray().title(
+                Span::styled(
+                    format!(
+                        "Events{} - Autoscroll: {} (press 'ESC' to previous screen, 'q' to quit)",
+                        if self.disconnected.load(Ordering::Relaxed) {
+                            " [disconnected]"
+                        } else {
+                            ""
+                        },
+                        if self.auto_scroll { "On" } else { "Off" }
+                    ),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+            ));
+        f.render_widget(paragraph, area);
+    }
+
+    pub(crate) fn get_bindings(&self) -> Option<&[(&str, &str)]> {
+        Some(&[("s", "Autoscroll")])
+    }
+
+    pub(crate) fn get_action(&self, k: &event::KeyEvent) -> Option<Action> {
+        match k.code {
+            KeyCode::Char('s') => Some(Action::AutoScroll),
+            _ => None,
+        }
+    }
+}
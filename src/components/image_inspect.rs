@@ -6,27 +6,29 @@ use ratatui::{
 };
 use tokio::sync::mpsc::UnboundedSender;
 
-use crate::{
-    action::Action,
-    components::{images::Images, Component},
-};
+use crate::{action::Action, components::Fallback, runtime::ImageDetails, utils::short_id};
 
 #[derive(Clone, Debug)]
 pub struct ImageInspect {
     id: String,
     name: String,
     details: String,
+    summary: Option<ImageDetails>,
+    used_by: Vec<String>,
     vertical_scroll_state: ScrollbarState,
     vertical_scroll: usize,
     action_tx: Option<UnboundedSender<Action>>,
 }
 
 impl ImageInspect {
-    pub fn new(id: String, name: String, details: String) -> Self {
+    pub fn new(id: String, name: String, details: String, used_by: Vec<String>) -> Self {
+        let summary = ImageDetails::parse(&details);
         ImageInspect {
             id,
             name,
             details,
+            summary,
+            used_by,
             vertical_scroll_state: Default::default(),
             vertical_scroll: 0,
             action_tx: None,
@@ -55,7 +57,7 @@ impl ImageInspect {
         match action {
             Action::PreviousScreen => {
                 if let Some(tx) = self.action_tx.clone() {
-                    tx.send(Action::Screen(Component::Images(Images::new())))?;
+                    tx.send(Action::Back(Fallback::Images))?;
                 }
             }
             Action::Up => {
@@ -76,6 +78,33 @@ impl ImageInspect {
     }
 
     pub(crate) fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let mut lines: Vec<Line> = self
+            .summary
+            .as_ref()
+            .map(|summary| summary.into())
+            .unwrap_or_default();
+        lines.push(Line::from(vec![
+            Span::styled("Used by: ", Style::default().bold()),
+            Span::raw(format!("{} containers", self.used_by.len())),
+        ]));
+        lines.extend(
+            self.used_by
+                .iter()
+                .map(|name| Line::styled(format!("  - {}", name), Style::default().gray())),
+        );
+        let rects = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(lines.len() as u16 + 2),
+                Constraint::Min(3),
+            ])
+            .split(area);
+        let summary =
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).gray().title(
+                Span::styled("Summary", Style::default().add_modifier(Modifier::BOLD)),
+            ));
+        f.render_widget(summary, rects[0]);
+        let area = rects[1];
         let image_details = Paragraph::new(self.details.clone())
             .gray()
             .block(
@@ -85,7 +114,7 @@ impl ImageInspect {
                     .title(Span::styled(
                         format!(
                         "Inspecting image: \"{}/{}\" (press 'ESC' to previous screen, 'q' to quit)",
-                        &self.id[0..12],
+                        short_id(&self.id),
                         self.name
                     ),
                         Style::default().add_modifier(Modifier::BOLD),
@@ -1,22 +1,37 @@
+use std::collections::HashSet;
+
 use color_eyre::Result;
 
-use crossterm::event::KeyCode;
+use crossterm::event::{self, KeyCode, KeyEventKind};
+use futures::StreamExt;
+use humansize::{FormatSize, BINARY};
 use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::style::{Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph, TableState, Wrap};
 use ratatui::Frame;
+use tokio::spawn;
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::action::Action;
-use crate::runtime::{delete_image, get_image, list_images, Filter, ImageSummary};
+use crate::runtime::{
+    delete_image, get_image, list_images, load_image, pull_image, save_image, Filter, ImageSummary,
+    NameFilter, RegistryCredentials, Unauthorized,
+};
 
-use crate::components::{containers::Containers, image_inspect::ImageInspect, Component};
-use crate::utils::{centered_rect, table};
+use crate::components::{
+    containers::Containers, image_inspect::ImageInspect, input::TextInput, Component,
+};
+use crate::utils::{
+    centered_rect, confirmation_popup, containers_using, delete_all, delete_popup, delete_selected,
+    empty_state, get_data_dir, request_confirmation, retain_listed, selection_style, table,
+    table_page_size, toggle_selection,
+};
 
-const IMAGE_CONSTRAINTS: [Constraint; 4] = [
+const IMAGE_CONSTRAINTS: [Constraint; 5] = [
     Constraint::Max(15),
     Constraint::Min(35),
+    Constraint::Max(15),
     Constraint::Max(10),
     Constraint::Max(20),
 ];
@@ -24,17 +39,171 @@ const IMAGE_CONSTRAINTS: [Constraint; 4] = [
 #[derive(Clone, Debug)]
 enum Popup {
     None,
-    Delete(String, String),
+    DeleteSelected,
+    Delete(String, String, Vec<String>),
+    Prune,
+    Pull(PullPopup),
+    Save(String, TextInput),
+    Load(TextInput),
+}
+
+#[derive(Clone, Debug, Default)]
+struct PullPopup {
+    reference: TextInput,
+    username: TextInput,
+    password: TextInput,
+    // 0: reference, 1: username, 2: password
+    focus: usize,
+    auth: bool,
+}
+
+impl PullPopup {
+    fn credentials(reference: String) -> Self {
+        PullPopup {
+            reference: TextInput::new(&reference),
+            focus: 1,
+            auth: true,
+            ..Default::default()
+        }
+    }
+
+    fn focused(&mut self) -> &mut TextInput {
+        match self.focus {
+            1 => &mut self.username,
+            2 => &mut self.password,
+            _ => &mut self.reference,
+        }
+    }
+}
+
+async fn save(id: String, name: String, dest: String, tx: UnboundedSender<Action>) -> Result<()> {
+    match save_image(&id, dest.clone().into()).await {
+        Ok(size) => tx.send(Action::Info(format!(
+            "Image \"{}\" saved to {} ({})",
+            name,
+            dest,
+            size.format_size(BINARY)
+        )))?,
+        Err(e) => tx.send(Action::Error(format!(
+            "Unable to save image \"{}\":\n{}",
+            name, e
+        )))?,
+    }
+    Ok(())
+}
+
+async fn prune(tx: UnboundedSender<Action>) -> Result<()> {
+    let dangling = match list_images(&Filter::default().dangling()).await {
+        Ok(dangling) => dangling,
+        Err(e) => {
+            tx.send(Action::Error(format!(
+                "Unable to list the dangling images:\n{}",
+                e
+            )))?;
+            return Ok(());
+        }
+    };
+    if dangling.is_empty() {
+        tx.send(Action::Info("No dangling images to prune".to_string()))?;
+        return Ok(());
+    }
+    let ids = dangling.into_iter().map(|i| i.id).collect();
+    let (count, errors) = delete_all(ids, |id| async move { delete_image(&id).await }).await;
+    tx.send(Action::Info(format!(
+        "Pruned {} of {} dangling images",
+        count - errors.len(),
+        count
+    )))?;
+    if !errors.is_empty() {
+        tx.send(Action::Error(format!(
+            "Unable to prune {} dangling images:\n{}",
+            errors.len(),
+            errors.join("\n")
+        )))?;
+    }
+    tx.send(Action::Tick)?;
+    Ok(())
+}
+
+async fn load(src: String, tx: UnboundedSender<Action>) -> Result<()> {
+    let result = async {
+        let mut stream = load_image(src.clone().into()).await?;
+        let mut loaded = Vec::new();
+        while let Some(status) = stream.next().await {
+            let status = status?;
+            log::debug!("Loading {}: {}", src, status);
+            if let Some(image) = status.trim().strip_prefix("Loaded image: ") {
+                loaded.push(image.to_string());
+            }
+        }
+        Ok::<Vec<String>, color_eyre::Report>(loaded)
+    }
+    .await;
+    match result {
+        Ok(loaded) => {
+            tx.send(Action::Info(format!(
+                "Loaded {} from {}",
+                if loaded.is_empty() {
+                    "images".to_string()
+                } else {
+                    loaded.join(", ")
+                },
+                src
+            )))?;
+            tx.send(Action::Tick)?;
+        }
+        Err(e) => tx.send(Action::Error(format!(
+            "Unable to load images from {}:\n{}",
+            src, e
+        )))?,
+    }
+    Ok(())
+}
+
+async fn pull(
+    reference: String,
+    credentials: Option<RegistryCredentials>,
+    tx: UnboundedSender<Action>,
+) -> Result<()> {
+    let result = async {
+        let mut stream = pull_image(&reference, credentials).await?;
+        while let Some(status) = stream.next().await {
+            log::debug!("Pulling {}: {}", reference, status?);
+        }
+        Ok::<(), color_eyre::Report>(())
+    }
+    .await;
+    match result {
+        Ok(()) => {
+            tx.send(Action::Info(format!("Image \"{}\" pulled", reference)))?;
+            tx.send(Action::Tick)?;
+        }
+        Err(e) if e.downcast_ref::<Unauthorized>().is_some() => {
+            tx.send(Action::Error(format!(
+                "Registry refused to pull \"{}\":\n{}",
+                reference, e
+            )))?;
+            tx.send(Action::PullCredentials(reference))?;
+        }
+        Err(e) => tx.send(Action::Error(format!(
+            "Unable to pull image \"{}\":\n{}",
+            reference, e
+        )))?,
+    }
+    Ok(())
 }
 
 #[derive(Clone, Debug)]
 pub struct Images {
     state: TableState,
+    page_size: usize,
     images: Vec<ImageSummary>,
     show_popup: Popup,
+    selected: HashSet<String>,
     action_tx: Option<UnboundedSender<Action>>,
     sort_by: SortColumn,
-    filter: Option<String>,
+    filter: Filter,
+    name_filter: Option<NameFilter>,
 }
 
 #[derive(Clone, Debug)]
@@ -51,15 +220,30 @@ pub enum SortColumn {
     Age(SortOrder),
 }
 
+impl SortColumn {
+    fn indicator(&self) -> (usize, bool) {
+        let (column, order) = match self {
+            SortColumn::Id(o) => (0, o),
+            SortColumn::Name(o) => (1, o),
+            SortColumn::Size(o) => (3, o),
+            SortColumn::Age(o) => (4, o),
+        };
+        (column, matches!(order, SortOrder::Asc))
+    }
+}
+
 impl Images {
     pub fn new() -> Self {
         Images {
             state: Default::default(),
+            page_size: 1,
             images: Vec::new(),
             show_popup: Popup::None,
+            selected: HashSet::new(),
             action_tx: None,
             sort_by: SortColumn::Age(SortOrder::Asc),
-            filter: None,
+            filter: Filter::default(),
+            name_filter: None,
         }
     }
 
@@ -95,6 +279,57 @@ impl Images {
         }
     }
 
+    fn prune_selected(&mut self) {
+        retain_listed(&mut self.selected, self.images.iter().map(|i| &i.id));
+    }
+
+    fn apply_name_filter(&mut self) {
+        if let Some(f) = &self.name_filter {
+            self.images.retain(|i| f.matches(&i.name));
+        }
+    }
+
+    fn toggle_selected(&mut self) {
+        let id = self
+            .state
+            .selected()
+            .and_then(|i| self.images.get(i))
+            .map(|i| &i.id);
+        if toggle_selection(&mut self.selected, id) {
+            self.page_down(1);
+        }
+    }
+
+    fn first(&mut self) {
+        if !self.images.is_empty() {
+            self.state.select(Some(0));
+        }
+    }
+
+    fn last(&mut self) {
+        if !self.images.is_empty() {
+            self.state.select(Some(self.images.len() - 1));
+        }
+    }
+
+    fn page_up(&mut self, qty: usize) {
+        if !self.images.is_empty() {
+            let i = self.state.selected().unwrap_or(0).saturating_sub(qty);
+            self.state.select(Some(i));
+        }
+    }
+
+    fn page_down(&mut self, qty: usize) {
+        if !self.images.is_empty() {
+            let i = self
+                .state
+                .selected()
+                .map_or(0, |i| i.saturating_add(qty))
+                .min(self.images.len() - 1);
+            self.state.select(Some(i));
+        }
+    }
+
     fn get_selected_image_info(&self) -> Option<(String, String)> {
         self.state
             .selected()
@@ -103,13 +338,29 @@ impl Images {
     }
 
     fn draw_popup(&self, f: &mut Frame<'_>) {
-        if let Popup::Delete(_id, tag) = &self.show_popup {
+        if let Popup::DeleteSelected = &self.show_popup {
+            confirmation_popup(
+                f,
+                Line::from(format!(
+                    "Are you sure you want to delete {} images?",
+                    self.selected.len()
+                )),
+            );
+        }
+        if let Popup::Prune = &self.show_popup {
+            confirmation_popup(
+                f,
+                Line::from("Are you sure you want to prune the dangling images?"),
+            );
+        }
+        if let Popup::Save(_, path) | Popup::Load(path) = &self.show_popup {
             let text = vec![
-                Line::from(vec![
-                    Span::raw("Are you sure you want to delete image: \""),
-                    Span::styled(tag, Style::new().gray()),
-                    Span::raw("\"?"),
-                ]),
+                Line::from(match self.show_popup {
+                    Popup::Load(_) => "Path of the tar archive to load:",
+                    _ => "Path of the tar archive to write:",
+                }),
+                Line::from(""),
+                Line::from(format!("> {}", path.value())),
                 Line::from(""),
                 Line::from(vec![
                     "ESC".bold(),
@@ -121,13 +372,63 @@ impl Images {
             let paragraph = Paragraph::new(text).wrap(Wrap { trim: false });
 
             let block = Block::default()
-                .title("Confirmation".bold())
+                .title(match self.show_popup {
+                    Popup::Load(_) => "Load images".bold(),
+                    _ => "Save image".bold(),
+                })
+                .padding(Padding::new(1, 1, 1, 1))
+                .borders(Borders::ALL);
+            let area = centered_rect(50, 10, f.size());
+            f.render_widget(Clear, area); //this clears out the background
+            f.render_widget(paragraph.block(block), area);
+        }
+        if let Popup::Pull(pull) = &self.show_popup {
+            let field = |label: &str, value: String, index: usize| {
+                let style = if pull.focus == index {
+                    Style::new().bold()
+                } else {
+                    Style::new().gray()
+                };
+                Line::from(vec![
+                    Span::raw(format!("{:<10}> ", label)),
+                    Span::styled(value, style),
+                ])
+            };
+            let mut text = vec![field("Image", pull.reference.value().to_string(), 0)];
+            if pull.auth {
+                text.push(field("Username", pull.username.value().to_string(), 1));
+                text.push(field(
+                    "Password",
+                    "*".repeat(pull.password.value().chars().count()),
+                    2,
+                ));
+            }
+            text.push(Line::from(""));
+            text.push(Line::from(vec![
+                "ESC".bold(),
+                " to Cancel, ".into(),
+                "Tab".bold(),
+                " to switch field, ".into(),
+                "Enter".bold(),
+                " to Pull".into(),
+            ]));
+            let paragraph = Paragraph::new(text).wrap(Wrap { trim: false });
+
+            let block = Block::default()
+                .title(if pull.auth {
+                    "Pull image - credentials required".bold()
+                } else {
+                    "Pull image".bold()
+                })
                 .padding(Padding::new(1, 1, 1, 1))
                 .borders(Borders::ALL);
-            let area = centered_rect(50, 8, f.size());
+            let area = centered_rect(50, 10, f.size());
             f.render_widget(Clear, area); //this clears out the background
             f.render_widget(paragraph.block(block), area);
         }
+        if let Popup::Delete(_id, tag, used_by) = &self.show_popup {
+            delete_popup(f, "image", tag, used_by);
+        }
     }
 
     fn sort(&mut self) {
@@ -158,6 +459,9 @@ impl Images {
         match action {
             Action::Tick => {
                 self.images = list_images(&self.filter).await?;
+                tx.send(Action::Refreshed)?;
+                self.apply_name_filter();
+                self.prune_selected();
                 self.sort();
                 if self.state.selected().is_none() {
                     self.state.select(Some(0));
@@ -169,14 +473,32 @@ impl Images {
             Action::Up => {
                 self.previous();
             }
+            Action::Top => {
+                self.first();
+            }
+            Action::Bottom => {
+                self.last();
+            }
+            Action::PageUp => {
+                self.page_up(self.page_size);
+            }
+            Action::PageDown => {
+                self.page_down(self.page_size);
+            }
             Action::Inspect => {
                 if let Some(info) = self.get_selected_image_info() {
                     let id = info.0.to_string();
                     let name = info.1.to_string();
                     let action = match get_image(&id).await {
-                        Ok(details) => Action::Screen(Component::ImageInspect(ImageInspect::new(
-                            id, name, details,
-                        ))),
+                        Ok(details) => {
+                            let used_by = containers_using(
+                                &Filter::default().filter("ancestor".to_string(), id.clone()),
+                            )
+                            .await;
+                            Action::Screen(Component::ImageInspect(ImageInspect::new(
+                                id, name, details, used_by,
+                            )))
+                        }
                         Err(e) => Action::Error(format!(
                             "Unable to get image \"{}\" details:\n{}",
                             name, e
@@ -186,15 +508,100 @@ impl Images {
                 };
             }
             Action::SetFilter(filter) => {
-                self.filter = filter;
+                self.filter = filter.into();
+                self.name_filter = None;
+            }
+            Action::SetNameFilter(name_filter) => {
+                self.name_filter = name_filter;
+                self.apply_name_filter();
+            }
+            Action::Select => {
+                self.toggle_selected();
+            }
+            Action::Delete if !self.selected.is_empty() => {
+                self.show_popup = Popup::DeleteSelected;
+                request_confirmation(&tx)?;
             }
             Action::Delete => {
                 if let Some((id, tag)) = self.get_selected_image_info() {
-                    self.show_popup = Popup::Delete(id, tag);
+                    let used_by = containers_using(
+                        &Filter::default().filter("ancestor".to_string(), id.clone()),
+                    )
+                    .await;
+                    // An image in use is never deleted without a confirmation
+                    if used_by.is_empty() {
+                        request_confirmation(&tx)?;
+                    }
+                    self.show_popup = Popup::Delete(id, tag, used_by);
+                }
+            }
+            Action::Save => {
+                if let Some((id, name)) = self.get_selected_image_info() {
+                    let file = format!("{}.tar", name.replace(['/', ':'], "_"));
+                    let dest = get_data_dir().join("images").join(file);
+                    self.show_popup = Popup::Save(id, TextInput::new(&dest.display().to_string()));
                 }
             }
+            Action::Ok if matches!(self.show_popup, Popup::Save(_, _)) => {
+                if let Popup::Save(id, dest) = std::mem::replace(&mut self.show_popup, Popup::None)
+                {
+                    let name = self
+                        .images
+                        .iter()
+                        .find(|i| i.id == id)
+                        .map_or(id.clone(), |i| i.name.clone());
+                    tx.send(Action::Info(format!("Saving image \"{}\"...", name)))?;
+                    spawn(save(id, name, dest.value().to_string(), tx.clone()));
+                }
+            }
+            Action::Load => {
+                let dir = get_data_dir().join("images").join("");
+                self.show_popup = Popup::Load(TextInput::new(&dir.display().to_string()));
+            }
+            Action::Ok if matches!(self.show_popup, Popup::Load(_)) => {
+                if let Popup::Load(src) = std::mem::replace(&mut self.show_popup, Popup::None) {
+                    let src = src.value().to_string();
+                    tx.send(Action::Info(format!("Loading images from {}...", src)))?;
+                    spawn(load(src, tx.clone()));
+                }
+            }
+            Action::Pull => {
+                self.show_popup = Popup::Pull(PullPopup::default());
+            }
+            Action::PullCredentials(reference) => {
+                self.show_popup = Popup::Pull(PullPopup::credentials(reference));
+            }
+            Action::Ok if matches!(self.show_popup, Popup::Pull(_)) => {
+                if let Popup::Pull(popup) = std::mem::replace(&mut self.show_popup, Popup::None) {
+                    let credentials = popup.auth.then(|| RegistryCredentials {
+                        username: popup.username.value().to_string(),
+                        password: popup.password.value().to_string(),
+                    });
+                    let reference = popup.reference.value().to_string();
+                    tx.send(Action::Info(format!("Pulling image \"{}\"...", reference)))?;
+                    spawn(pull(reference, credentials, tx.clone()));
+                }
+            }
+            Action::Prune => {
+                self.show_popup = Popup::Prune;
+                request_confirmation(&tx)?;
+            }
+            Action::Ok if matches!(self.show_popup, Popup::Prune) => {
+                self.show_popup = Popup::None;
+                tx.send(Action::Info("Pruning dangling images...".to_string()))?;
+                spawn(prune(tx.clone()));
+            }
+            Action::Ok if matches!(self.show_popup, Popup::DeleteSelected) => {
+                spawn(delete_selected(
+                    "images",
+                    self.selected.drain().collect(),
+                    |id| async move { delete_image(&id).await },
+                    tx.clone(),
+                ));
+                self.show_popup = Popup::None;
+            }
             Action::Ok => {
-                if let Popup::Delete(id, _) = &self.show_popup.clone() {
+                if let Popup::Delete(id, _, _) = &self.show_popup.clone() {
                     if let Err(e) = delete_image(id).await {
                         tx.send(Action::Error(format!(
                             "Unable to delete container \"{}\" {}",
@@ -232,19 +639,39 @@ impl Images {
             .split(area);
         let t = table(
             format!(
-                "{}{}",
+                "{}{}{}",
                 self.get_name(),
-                match &self.filter {
-                    Some(f) => format!(" - Filter: {}", f),
-                    None => "".to_string(),
-                }
+                self.filter.format(),
+                self.name_filter
+                    .as_ref()
+                    .map(NameFilter::format)
+                    .unwrap_or_default()
             ),
-            ["Id", "Name", "Size", "Age"],
-            self.images.iter().map(|i| i.into()).collect(),
+            &["Id", "Name", "Platform", "Size", "Age"],
+            self.images
+                .iter()
+                .map(|i| selection_style(i.into(), self.selected.contains(&i.id)))
+                .collect(),
             &IMAGE_CONSTRAINTS,
             Some(Style::new().gray()),
+            Some(self.sort_by.indicator()),
         );
+        self.page_size = table_page_size(rects[0]);
         f.render_stateful_widget(t, rects[0], &mut self.state);
+        if self.images.is_empty() {
+            empty_state(
+                f,
+                rects[0],
+                "images",
+                &[
+                    self.filter.to_string(),
+                    self.name_filter
+                        .as_ref()
+                        .map(|f| f.to_string())
+                        .unwrap_or_default(),
+                ],
+            );
+        }
 
         self.draw_popup(f);
     }
@@ -252,6 +679,11 @@ impl Images {
     pub(crate) fn get_bindings(&self) -> Option<&[(&str, &str)]> {
         Some(&[
             ("ctrl+d", "Delete"),
+            ("Space", "Select/Unselect"),
+            ("p", "Pull an image"),
+            ("s", "Save the image to a tar file"),
+            ("L", "Load images from a tar file"),
+            ("P", "Prune dangling images"),
             ("i", "Inspect/View details"),
             ("c", "Show containers"),
             ("F1", "Sort by image id"),
@@ -265,18 +697,74 @@ impl Images {
         match k.code {
             KeyCode::Char('c') => {
                 if let Some((id, _)) = self.get_selected_image_info() {
-                    Some(Action::Screen(Component::Containers(Containers::new(
-                        Filter::default().filter("ancestor".to_string(), id),
+                    Some(Action::Screen(Component::Containers(Box::new(
+                        Containers::new(Filter::default().filter("ancestor".to_string(), id)),
                     ))))
                 } else {
                     None
                 }
             }
             KeyCode::Char('i') => Some(Action::Inspect),
+            KeyCode::Char(' ') => Some(Action::Select),
+            KeyCode::Char('p') => Some(Action::Pull),
+            KeyCode::Char('s') => Some(Action::Save),
+            KeyCode::Char('L') => Some(Action::Load),
+            KeyCode::Char('P') => Some(Action::Prune),
             _ => None,
         }
     }
 
+    pub(crate) fn handle_input(
+        &mut self,
+        kevent: event::KeyEvent,
+    ) -> Result<Option<event::KeyEvent>> {
+        match &mut self.show_popup {
+            Popup::Pull(pull) if kevent.kind == KeyEventKind::Press => match kevent.code {
+                KeyCode::Tab | KeyCode::Down if pull.auth => {
+                    pull.focus = (pull.focus + 1) % 3;
+                    Ok(None)
+                }
+                KeyCode::BackTab | KeyCode::Up if pull.auth => {
+                    pull.focus = (pull.focus + 2) % 3;
+                    Ok(None)
+                }
+                KeyCode::Esc => {
+                    self.show_popup = Popup::None;
+                    Ok(None)
+                }
+                KeyCode::Enter => Ok(Some(kevent)),
+                _ => {
+                    pull.focused().handle_key(&kevent);
+                    Ok(None)
+                }
+            },
+            Popup::Pull(_) => Ok(None),
+            Popup::Save(_, path) | Popup::Load(path) if kevent.kind == KeyEventKind::Press => {
+                match kevent.code {
+                    KeyCode::Esc => {
+                        self.show_popup = Popup::None;
+                        Ok(None)
+                    }
+                    KeyCode::Enter => Ok(Some(kevent)),
+                    _ => {
+                        path.handle_key(&kevent);
+                        Ok(None)
+                    }
+                }
+            }
+            Popup::Save(_, _) | Popup::Load(_) => Ok(None),
+            _ => Ok(Some(kevent)),
+        }
+    }
+
+    pub(crate) fn handle_paste(&mut self, text: &str) {
+        match &mut self.show_popup {
+            Popup::Pull(pull) => pull.focused().paste(text),
+            Popup::Save(_, path) | Popup::Load(path) => path.paste(text),
+            _ => {}
+        }
+    }
+
     pub(crate) fn has_filter(&self) -> bool {
         true
     }
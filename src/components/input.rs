@@ -0,0 +1,191 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::utils::byte_index;
+
+// Single line text input (command line and popups), the cursor is counted in chars
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TextInput {
+    value: String,
+    cursor: usize,
+}
+
+impl TextInput {
+    // Prefilled input, with the cursor at its end
+    pub fn new(value: &str) -> Self {
+        TextInput {
+            value: value.to_string(),
+            cursor: value.chars().count(),
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn clear(&mut self) {
+        self.value.clear();
+        self.cursor = 0;
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.value.insert(byte_index(&self.value, self.cursor), c);
+        self.right();
+    }
+
+    // The input is single line: line breaks are replaced by spaces, other control chars dropped
+    pub fn paste(&mut self, text: &str) {
+        let text: String = text
+            .trim_end_matches(['\r', '\n'])
+            .replace("\r\n", " ")
+            .chars()
+            .filter_map(|c| match c {
+                '\n' | '\r' | '\t' => Some(' '),
+                c if c.is_control() => None,
+                c => Some(c),
+            })
+            .collect();
+        self.value
+            .insert_str(byte_index(&self.value, self.cursor), &text);
+        self.cursor += text.chars().count();
+    }
+
+    pub fn delete(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.value.remove(byte_index(&self.value, self.cursor));
+        }
+    }
+
+    pub fn left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.value.chars().count());
+    }
+
+    // Words are separated by whitespaces, like in a shell
+    pub fn word_left(&mut self) {
+        let chars: Vec<char> = self.value.chars().take(self.cursor).collect();
+        let mut i = chars.len();
+        while i > 0 && chars[i - 1].is_whitespace() {
+            i -= 1;
+        }
+        while i > 0 && !chars[i - 1].is_whitespace() {
+            i -= 1;
+        }
+        self.cursor = i;
+    }
+
+    pub fn word_right(&mut self) {
+        let chars: Vec<char> = self.value.chars().collect();
+        let mut i = self.cursor.min(chars.len());
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+        while i < chars.len() && !chars[i].is_whitespace() {
+            i += 1;
+        }
+        self.cursor = i;
+    }
+
+    // Deletes the word before the cursor, like `Ctrl+W` in a shell
+    pub fn delete_word(&mut self) {
+        let end = byte_index(&self.value, self.cursor);
+        self.word_left();
+        let start = byte_index(&self.value, self.cursor);
+        self.value.replace_range(start..end, "");
+    }
+
+    // Returns false when the key is not an editing one and should be handled by the caller
+    pub fn handle_key(&mut self, kevent: &KeyEvent) -> bool {
+        let ctrl = kevent.modifiers.contains(KeyModifiers::CONTROL);
+        match kevent.code {
+            KeyCode::Char('w') if ctrl => self.delete_word(),
+            KeyCode::Char(c) => self.insert(c),
+            KeyCode::Backspace => self.delete(),
+            KeyCode::Left if ctrl => self.word_left(),
+            KeyCode::Right if ctrl => self.word_right(),
+            KeyCode::Left => self.left(),
+            KeyCode::Right => self.right(),
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_delete_multibyte() {
+        let mut input = TextInput::new("héllo");
+        input.left();
+        input.left();
+        input.insert('ß');
+        assert_eq!(input.value(), "hélßlo");
+        assert_eq!(input.cursor(), 4);
+        input.delete();
+        input.delete();
+        assert_eq!(input.value(), "hélo");
+        assert_eq!(input.cursor(), 2);
+        input.left();
+        input.delete();
+        assert_eq!(input.value(), "élo");
+        assert_eq!(input.cursor(), 0);
+        input.delete();
+        assert_eq!(input.value(), "élo");
+    }
+
+    #[test]
+    fn word_moves() {
+        let mut input = TextInput::new("  ünïcode  wörds ");
+        input.word_left();
+        assert_eq!(input.cursor(), 11);
+        input.word_left();
+        assert_eq!(input.cursor(), 2);
+        input.word_left();
+        assert_eq!(input.cursor(), 0);
+        input.word_right();
+        assert_eq!(input.cursor(), 9);
+        input.word_right();
+        assert_eq!(input.cursor(), 16);
+        input.word_right();
+        assert_eq!(input.cursor(), 17);
+        input.word_right();
+        assert_eq!(input.cursor(), 17);
+    }
+
+    #[test]
+    fn delete_word() {
+        let mut input = TextInput::new("docker run  ça");
+        input.delete_word();
+        assert_eq!(input.value(), "docker run  ");
+        assert_eq!(input.cursor(), 12);
+        input.delete_word();
+        assert_eq!(input.value(), "docker ");
+        input.left();
+        input.left();
+        input.delete_word();
+        assert_eq!(input.value(), "r ");
+        assert_eq!(input.cursor(), 0);
+        input.delete_word();
+        assert_eq!(input.value(), "r ");
+    }
+
+    #[test]
+    fn paste_advances_cursor() {
+        let mut input = TextInput::new("ab");
+        input.left();
+        input.paste("é\tz\r\nx\u{7}\n");
+        assert_eq!(input.value(), "aé z xb");
+        assert_eq!(input.cursor(), 6);
+        input.insert('!');
+        assert_eq!(input.value(), "aé z x!b");
+    }
+}
@@ -0,0 +1,142 @@
+use color_eyre::Result;
+
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph, ScrollbarState},
+};
+use similar::{ChangeTag, TextDiff};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::action::Action;
+use crate::components::Fallback;
+use crate::utils::short_id;
+
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Clone, Debug)]
+pub struct InspectDiff {
+    left: (String, String),
+    right: (String, String),
+    lines: Vec<Line<'static>>,
+    additions: usize,
+    removals: usize,
+    vertical_scroll_state: ScrollbarState,
+    vertical_scroll: usize,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl InspectDiff {
+    // Each side is the id, the name and the inspect JSON of a container
+    pub fn new(left: (String, String, String), right: (String, String, String)) -> Self {
+        let (lcid, lname, ldetails) = left;
+        let (rcid, rname, rdetails) = right;
+        let diff = TextDiff::from_lines(&ldetails, &rdetails);
+        let mut lines = vec![];
+        let (mut additions, mut removals) = (0, 0);
+        for hunk in diff
+            .unified_diff()
+            .context_radius(CONTEXT_LINES)
+            .iter_hunks()
+        {
+            lines.push(Line::styled(hunk.header().to_string(), Style::new().cyan()));
+            for change in hunk.iter_changes() {
+                let (sign, style) = match change.tag() {
+                    ChangeTag::Delete => {
+                        removals += 1;
+                        ("-", Style::new().red())
+                    }
+                    ChangeTag::Insert => {
+                        additions += 1;
+                        ("+", Style::new().green())
+                    }
+                    ChangeTag::Equal => (" ", Style::new().gray()),
+                };
+                lines.push(Line::styled(
+                    format!("{}{}", sign, change.value().trim_end_matches('\n')),
+                    style,
+                ));
+            }
+        }
+        InspectDiff {
+            left: (lcid, lname),
+            right: (rcid, rname),
+            lines,
+            additions,
+            removals,
+            vertical_scroll_state: Default::default(),
+            vertical_scroll: 0,
+            action_tx: None,
+        }
+    }
+
+    fn down(&mut self, qty: usize) {
+        self.vertical_scroll = self.vertical_scroll.saturating_add(qty);
+        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+    }
+
+    fn up(&mut self, qty: usize) {
+        self.vertical_scroll = self.vertical_scroll.saturating_sub(qty);
+        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+    }
+
+    pub(crate) fn get_name(&self) -> &'static str {
+        "InspectDiff"
+    }
+
+    pub(crate) fn register_action_handler(&mut self, tx: UnboundedSender<Action>) {
+        self.action_tx = Some(tx);
+    }
+
+    pub(crate) async fn update(&mut self, action: Action) -> Result<()> {
+        match action {
+            Action::PreviousScreen => {
+                if let Some(tx) = self.action_tx.clone() {
+                    tx.send(Action::Back(Fallback::Containers))?;
+                }
+            }
+            Action::Up => {
+                self.up(1);
+            }
+            Action::Down => {
+                self.down(1);
+            }
+            Action::PageUp => {
+                self.up(15);
+            }
+            Action::PageDown => {
+                self.down(15);
+            }
+            _ => {}
+        };
+        Ok(())
+    }
+
+    pub(crate) fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let text = if self.lines.is_empty() {
+            vec![Line::from("No differences")]
+        } else {
+            self.lines.clone()
+        };
+        let diff = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .gray()
+                    .title(Span::styled(
+                        format!(
+                            "Diff between containers: -\"{}/{}\" +\"{}/{}\" ({} removed, {} added lines) (press 'ESC' to previous screen, 'q' to quit)",
+                            short_id(&self.left.0),
+                            self.left.1,
+                            short_id(&self.right.0),
+                            self.right.1,
+                            self.removals,
+                            self.additions
+                        ),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    )),
+            )
+            .scroll((self.vertical_scroll as u16, 0));
+
+        f.render_widget(diff, area);
+    }
+}
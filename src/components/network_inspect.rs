@@ -6,9 +6,7 @@ use ratatui::{
 };
 use tokio::sync::mpsc::UnboundedSender;
 
-use crate::{action::Action, components::Component};
-
-use super::networks::Networks;
+use crate::{action::Action, components::Fallback, utils::short_id};
 
 #[derive(Clone, Debug)]
 pub struct NetworkInspect {
@@ -54,9 +52,7 @@ impl NetworkInspect {
         match action {
             Action::PreviousScreen => {
                 if let Some(tx) = self.action_tx.clone() {
-                    tx.send(Action::Screen(Component::Networks(Networks::new(
-                        Default::default(),
-                    ))))?;
+                    tx.send(Action::Back(Fallback::Networks))?;
                 }
             }
             Action::Up => {
@@ -86,7 +82,7 @@ impl NetworkInspect {
                     .title(Span::styled(
                         format!(
                         "Inspecting network: \"{}/{}\" (press 'ESC' to previous screen, 'q' to quit)",
-                        &self.id[0..12],
+                        short_id(&self.id),
                         self.name
                     ),
                         Style::default().add_modifier(Modifier::BOLD),
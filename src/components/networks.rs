@@ -1,17 +1,25 @@
+use std::collections::HashSet;
+
 use color_eyre::Result;
 
 use crossterm::event::{self, KeyCode};
 use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::style::{Style, Stylize};
-use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph, TableState, Wrap};
+use ratatui::text::Line;
+use ratatui::widgets::TableState;
 use ratatui::Frame;
+use tokio::spawn;
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::action::Action;
 use crate::components::{network_inspect::NetworkInspect, Component};
-use crate::runtime::{delete_network, get_network, list_networks, Filter, NetworkSummary};
-use crate::utils::{centered_rect, table};
+use crate::runtime::{
+    delete_network, get_network, list_networks, Filter, NameFilter, NetworkSummary,
+};
+use crate::utils::{
+    confirmation_popup, containers_using, delete_popup, delete_selected, empty_state,
+    request_confirmation, retain_listed, selection_style, table, table_page_size, toggle_selection,
+};
 
 const NETWORK_CONSTRAINTS: [Constraint; 4] = [
     Constraint::Max(15),
@@ -23,7 +31,8 @@ const NETWORK_CONSTRAINTS: [Constraint; 4] = [
 #[derive(Clone, Debug)]
 enum Popup {
     None,
-    Delete(String),
+    DeleteSelected,
+    Delete(String, Vec<String>),
 }
 
 #[derive(Clone, Debug)]
@@ -40,25 +49,43 @@ pub enum SortColumn {
     Age(SortOrder),
 }
 
+impl SortColumn {
+    fn indicator(&self) -> (usize, bool) {
+        let (column, order) = match self {
+            SortColumn::Id(o) => (0, o),
+            SortColumn::Name(o) => (1, o),
+            SortColumn::Driver(o) => (2, o),
+            SortColumn::Age(o) => (3, o),
+        };
+        (column, matches!(order, SortOrder::Asc))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Networks {
     state: TableState,
+    page_size: usize,
     networks: Vec<NetworkSummary>,
     show_popup: Popup,
+    selected: HashSet<String>,
     action_tx: Option<UnboundedSender<Action>>,
     sort_by: SortColumn,
     filter: Filter,
+    name_filter: Option<NameFilter>,
 }
 
 impl Networks {
     pub fn new(filter: Filter) -> Self {
         Networks {
             state: Default::default(),
+            page_size: 1,
             networks: Vec::new(),
             show_popup: Popup::None,
+            selected: HashSet::new(),
             action_tx: None,
             sort_by: SortColumn::Name(SortOrder::Asc),
             filter,
+            name_filter: None,
         }
     }
 
@@ -94,6 +121,57 @@ impl Networks {
         }
     }
 
+    fn prune_selected(&mut self) {
+        retain_listed(&mut self.selected, self.networks.iter().map(|n| &n.id));
+    }
+
+    fn apply_name_filter(&mut self) {
+        if let Some(f) = &self.name_filter {
+            self.networks.retain(|n| f.matches(&n.name));
+        }
+    }
+
+    fn toggle_selected(&mut self) {
+        let id = self
+            .state
+            .selected()
+            .and_then(|i| self.networks.get(i))
+            .map(|n| &n.id);
+        if toggle_selection(&mut self.selected, id) {
+            self.page_down(1);
+        }
+    }
+
+    fn first(&mut self) {
+        if !self.networks.is_empty() {
+            self.state.select(Some(0));
+        }
+    }
+
+    fn last(&mut self) {
+        if !self.networks.is_empty() {
+            self.state.select(Some(self.networks.len() - 1));
+        }
+    }
+
+    fn page_up(&mut self, qty: usize) {
+        if !self.networks.is_empty() {
+            let i = self.state.selected().unwrap_or(0).saturating_sub(qty);
+            self.state.select(Some(i));
+        }
+    }
+
+    fn page_down(&mut self, qty: usize) {
+        if !self.networks.is_empty() {
+            let i = self
+                .state
+                .selected()
+                .map_or(0, |i| i.saturating_add(qty))
+                .min(self.networks.len() - 1);
+            self.state.select(Some(i));
+        }
+    }
+
     fn get_selected_network_info(&self) -> Option<(String, String)> {
         self.state
             .selected()
@@ -102,30 +180,17 @@ impl Networks {
     }
 
     fn draw_popup(&self, f: &mut Frame<'_>) {
-        if let Popup::Delete(id) = &self.show_popup {
-            let text = vec![
-                Line::from(vec![
-                    Span::raw("Are you sure you want to delete network: \""),
-                    Span::styled(id, Style::new().gray()),
-                    Span::raw("\"?"),
-                ]),
-                Line::from(""),
-                Line::from(vec![
-                    "ESC".bold(),
-                    " to Cancel, ".into(),
-                    "Enter".bold(),
-                    " to Confirm".into(),
-                ]),
-            ];
-            let paragraph = Paragraph::new(text).wrap(Wrap { trim: false });
-
-            let block = Block::default()
-                .title("Confirmation".bold())
-                .padding(Padding::new(1, 1, 1, 1))
-                .borders(Borders::ALL);
-            let area = centered_rect(50, 8, f.size());
-            f.render_widget(Clear, area); //this clears out the background
-            f.render_widget(paragraph.block(block), area);
+        if let Popup::DeleteSelected = &self.show_popup {
+            confirmation_popup(
+                f,
+                Line::from(format!(
+                    "Are you sure you want to delete {} networks?",
+                    self.selected.len()
+                )),
+            );
+        }
+        if let Popup::Delete(id, used_by) = &self.show_popup {
+            delete_popup(f, "network", id, used_by);
         }
     }
 
@@ -158,6 +223,9 @@ impl Networks {
             Action::Tick => match list_networks(&self.filter).await {
                 Ok(networks) => {
                     self.networks = networks;
+                    tx.send(Action::Refreshed)?;
+                    self.apply_name_filter();
+                    self.prune_selected();
                     self.sort();
                     if self.state.selected().is_none() {
                         self.state.select(Some(0));
@@ -175,6 +243,18 @@ impl Networks {
             Action::Up => {
                 self.previous();
             }
+            Action::Top => {
+                self.first();
+            }
+            Action::Bottom => {
+                self.last();
+            }
+            Action::PageUp => {
+                self.page_up(self.page_size);
+            }
+            Action::PageDown => {
+                self.page_down(self.page_size);
+            }
             Action::Inspect => {
                 if let Some(info) = self.get_selected_network_info() {
                     let id = info.0.to_string();
@@ -193,14 +273,43 @@ impl Networks {
             }
             Action::SetFilter(filter) => {
                 self.filter = filter.into();
+                self.name_filter = None;
+            }
+            Action::SetNameFilter(name_filter) => {
+                self.name_filter = name_filter;
+                self.apply_name_filter();
+            }
+            Action::Select => {
+                self.toggle_selected();
+            }
+            Action::Delete if !self.selected.is_empty() => {
+                self.show_popup = Popup::DeleteSelected;
+                request_confirmation(&tx)?;
             }
             Action::Delete => {
                 if let Some((id, _)) = self.get_selected_network_info() {
-                    self.show_popup = Popup::Delete(id);
+                    let used_by = containers_using(
+                        &Filter::default().filter("network".to_string(), id.clone()),
+                    )
+                    .await;
+                    // Deleting a resource in use always requires a confirmation
+                    if used_by.is_empty() {
+                        request_confirmation(&tx)?;
+                    }
+                    self.show_popup = Popup::Delete(id, used_by);
                 }
             }
+            Action::Ok if matches!(self.show_popup, Popup::DeleteSelected) => {
+                spawn(delete_selected(
+                    "networks",
+                    self.selected.drain().collect(),
+                    |id| async move { delete_network(&id).await },
+                    tx.clone(),
+                ));
+                self.show_popup = Popup::None;
+            }
             Action::Ok => {
-                if let Popup::Delete(id) = &self.show_popup {
+                if let Popup::Delete(id, _) = &self.show_popup {
                     if let Err(e) = delete_network(id).await {
                         tx.send(Action::Error(format!(
                             "Unable to delete network \"{}\":\n{}",
@@ -218,7 +327,7 @@ impl Networks {
                 self.sort_by = match (n, &self.sort_by) {
                     (1, SortColumn::Id(SortOrder::Asc)) => SortColumn::Id(SortOrder::Desc),
                     (1, _) => SortColumn::Id(SortOrder::Asc),
-                    (2, SortColumn::Name(SortOrder::Asc)) => SortColumn::Age(SortOrder::Desc),
+                    (2, SortColumn::Name(SortOrder::Asc)) => SortColumn::Name(SortOrder::Desc),
                     (2, _) => SortColumn::Name(SortOrder::Asc),
                     (3, SortColumn::Driver(SortOrder::Asc)) => SortColumn::Driver(SortOrder::Desc),
                     (3, _) => SortColumn::Driver(SortOrder::Asc),
@@ -237,13 +346,40 @@ impl Networks {
             .constraints([Constraint::Percentage(100)])
             .split(area);
         let t = table(
-            format!("{}{}", self.get_name(), self.filter.format()),
-            ["Id", "Name", "Driver", "Age"],
-            self.networks.iter().map(|n| n.into()).collect(),
+            format!(
+                "{}{}{}",
+                self.get_name(),
+                self.filter.format(),
+                self.name_filter
+                    .as_ref()
+                    .map(NameFilter::format)
+                    .unwrap_or_default()
+            ),
+            &["Id", "Name", "Driver", "Age"],
+            self.networks
+                .iter()
+                .map(|n| selection_style(n.into(), self.selected.contains(&n.id)))
+                .collect(),
             &NETWORK_CONSTRAINTS,
             Some(Style::new().gray()),
+            Some(self.sort_by.indicator()),
         );
+        self.page_size = table_page_size(rects[0]);
         f.render_stateful_widget(t, rects[0], &mut self.state);
+        if self.networks.is_empty() {
+            empty_state(
+                f,
+                rects[0],
+                "networks",
+                &[
+                    self.filter.to_string(),
+                    self.name_filter
+                        .as_ref()
+                        .map(|f| f.to_string())
+                        .unwrap_or_default(),
+                ],
+            );
+        }
 
         self.draw_popup(f);
     }
@@ -251,6 +387,7 @@ impl Networks {
     pub(crate) fn get_bindings(&self) -> Option<&[(&str, &str)]> {
         Some(&[
             ("ctrl+d", "Delete"),
+            ("Space", "Select/Unselect"),
             ("i", "Inspect/View details"),
             ("F1", "Sort by network id"),
             ("F2", "Sort by network name"),
@@ -262,6 +399,7 @@ impl Networks {
     pub(crate) fn get_action(&self, k: &event::KeyEvent) -> Option<Action> {
         match k.code {
             KeyCode::Char('i') => Some(Action::Inspect),
+            KeyCode::Char(' ') => Some(Action::Select),
             _ => None,
         }
     }
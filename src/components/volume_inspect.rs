@@ -6,10 +6,7 @@ use ratatui::{
 };
 use tokio::sync::mpsc::UnboundedSender;
 
-use crate::{
-    action::Action,
-    components::{volumes::Volumes, Component},
-};
+use crate::{action::Action, components::Fallback, utils::short_id};
 
 #[derive(Clone, Debug)]
 pub struct VolumeInspect {
@@ -53,9 +50,7 @@ impl VolumeInspect {
         match action {
             Action::PreviousScreen => {
                 if let Some(tx) = self.action_tx.clone() {
-                    tx.send(Action::Screen(Component::Volumes(Volumes::new(
-                        Default::default(),
-                    ))))?;
+                    tx.send(Action::Back(Fallback::Volumes))?;
                 }
             }
             Action::Up => {
@@ -85,7 +80,7 @@ impl VolumeInspect {
                     .title(Span::styled(
                         format!(
                         "Inspecting volume: \"{}\" (press 'ESC' to previous screen, 'q' to quit)",
-                        &self.id[0..12]
+                        short_id(&self.id)
                     ),
                         Style::default().add_modifier(Modifier::BOLD),
                     )),
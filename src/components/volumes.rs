@@ -1,17 +1,24 @@
+use std::collections::HashSet;
+
 use color_eyre::Result;
 
 use crossterm::event::{self, KeyCode};
 use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::style::{Style, Stylize};
-use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph, TableState, Wrap};
+use ratatui::text::Line;
+use ratatui::widgets::TableState;
 use ratatui::Frame;
+use tokio::spawn;
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::action::Action;
 use crate::components::{Component, VolumeInspect};
-use crate::runtime::{delete_volume, get_volume, list_volumes, Filter, VolumeSummary};
-use crate::utils::{centered_rect, table};
+use crate::runtime::{delete_volume, get_volume, list_volumes, Filter, NameFilter, VolumeSummary};
+use crate::utils::{
+    confirmation_popup, containers_using, delete_popup, delete_selected, empty_state,
+    request_confirmation, retain_listed, selection_style, short_id, table, table_page_size,
+    toggle_selection,
+};
 
 const VOLUME_CONSTRAINTS: [Constraint; 3] = [
     Constraint::Max(15),
@@ -22,7 +29,8 @@ const VOLUME_CONSTRAINTS: [Constraint; 3] = [
 #[derive(Clone, Debug)]
 enum Popup {
     None,
-    Delete(String),
+    DeleteSelected,
+    Delete(String, Vec<String>),
 }
 
 #[derive(Clone, Debug)]
@@ -38,25 +46,42 @@ pub enum SortColumn {
     Age(SortOrder),
 }
 
+impl SortColumn {
+    fn indicator(&self) -> (usize, bool) {
+        let (column, order) = match self {
+            SortColumn::Id(o) => (0, o),
+            SortColumn::Driver(o) => (1, o),
+            SortColumn::Age(o) => (2, o),
+        };
+        (column, matches!(order, SortOrder::Asc))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Volumes {
     state: TableState,
+    page_size: usize,
     volumes: Vec<VolumeSummary>,
     show_popup: Popup,
+    selected: HashSet<String>,
     action_tx: Option<UnboundedSender<Action>>,
     sort_by: SortColumn,
     filter: Filter,
+    name_filter: Option<NameFilter>,
 }
 
 impl Volumes {
     pub fn new(filter: Filter) -> Self {
         Volumes {
             state: Default::default(),
+            page_size: 1,
             volumes: Vec::new(),
             show_popup: Popup::None,
+            selected: HashSet::new(),
             action_tx: None,
             sort_by: SortColumn::Id(SortOrder::Asc),
             filter,
+            name_filter: None,
         }
     }
 
@@ -92,6 +117,57 @@ impl Volumes {
         }
     }
 
+    fn prune_selected(&mut self) {
+        retain_listed(&mut self.selected, self.volumes.iter().map(|v| &v.id));
+    }
+
+    fn apply_name_filter(&mut self) {
+        if let Some(f) = &self.name_filter {
+            self.volumes.retain(|v| f.matches(&v.id));
+        }
+    }
+
+    fn toggle_selected(&mut self) {
+        let id = self
+            .state
+            .selected()
+            .and_then(|i| self.volumes.get(i))
+            .map(|v| &v.id);
+        if toggle_selection(&mut self.selected, id) {
+            self.page_down(1);
+        }
+    }
+
+    fn first(&mut self) {
+        if !self.volumes.is_empty() {
+            self.state.select(Some(0));
+        }
+    }
+
+    fn last(&mut self) {
+        if !self.volumes.is_empty() {
+            self.state.select(Some(self.volumes.len() - 1));
+        }
+    }
+
+    fn page_up(&mut self, qty: usize) {
+        if !self.volumes.is_empty() {
+            let i = self.state.selected().unwrap_or(0).saturating_sub(qty);
+            self.state.select(Some(i));
+        }
+    }
+
+    fn page_down(&mut self, qty: usize) {
+        if !self.volumes.is_empty() {
+            let i = self
+                .state
+                .selected()
+                .map_or(0, |i| i.saturating_add(qty))
+                .min(self.volumes.len() - 1);
+            self.state.select(Some(i));
+        }
+    }
+
     fn get_selected_volume_info(&self) -> Option<String> {
         self.state
             .selected()
@@ -100,30 +176,17 @@ impl Volumes {
     }
 
     fn draw_popup(&self, f: &mut Frame<'_>) {
-        if let Popup::Delete(id) = &self.show_popup {
-            let text = vec![
-                Line::from(vec![
-                    Span::raw("Are you sure you want to delete volume: \""),
-                    Span::styled(id, Style::new().gray()),
-                    Span::raw("\"?"),
-                ]),
-                Line::from(""),
-                Line::from(vec![
-                    "ESC".bold(),
-                    " to Cancel, ".into(),
-                    "Enter".bold(),
-                    " to Confirm".into(),
-                ]),
-            ];
-            let paragraph = Paragraph::new(text).wrap(Wrap { trim: false });
-
-            let block = Block::default()
-                .title("Confirmation".bold())
-                .padding(Padding::new(1, 1, 1, 1))
-                .borders(Borders::ALL);
-            let area = centered_rect(50, 8, f.size());
-            f.render_widget(Clear, area); //this clears out the background
-            f.render_widget(paragraph.block(block), area);
+        if let Popup::DeleteSelected = &self.show_popup {
+            confirmation_popup(
+                f,
+                Line::from(format!(
+                    "Are you sure you want to delete {} volumes?",
+                    self.selected.len()
+                )),
+            );
+        }
+        if let Popup::Delete(id, used_by) = &self.show_popup {
+            delete_popup(f, "volume", id, used_by);
         }
     }
 
@@ -155,6 +218,9 @@ impl Volumes {
             Action::Tick => match list_volumes(&self.filter).await {
                 Ok(volumes) => {
                     self.volumes = volumes;
+                    tx.send(Action::Refreshed)?;
+                    self.apply_name_filter();
+                    self.prune_selected();
                     self.sort();
                     if self.state.selected().is_none() {
                         self.state.select(Some(0));
@@ -168,6 +234,18 @@ impl Volumes {
             Action::Up => {
                 self.previous();
             }
+            Action::Top => {
+                self.first();
+            }
+            Action::Bottom => {
+                self.last();
+            }
+            Action::PageUp => {
+                self.page_up(self.page_size);
+            }
+            Action::PageDown => {
+                self.page_down(self.page_size);
+            }
             Action::Inspect => {
                 if let Some(info) = self.get_selected_volume_info() {
                     let id = info.to_string();
@@ -177,7 +255,7 @@ impl Volumes {
                         )),
                         Err(e) => Action::Error(format!(
                             "Unable to get network \"{}\" details:\n{}",
-                            &id[0..12],
+                            short_id(&id),
                             e
                         )),
                     };
@@ -186,14 +264,43 @@ impl Volumes {
             }
             Action::SetFilter(filter) => {
                 self.filter = filter.into();
+                self.name_filter = None;
+            }
+            Action::SetNameFilter(name_filter) => {
+                self.name_filter = name_filter;
+                self.apply_name_filter();
+            }
+            Action::Select => {
+                self.toggle_selected();
+            }
+            Action::Delete if !self.selected.is_empty() => {
+                self.show_popup = Popup::DeleteSelected;
+                request_confirmation(&tx)?;
             }
             Action::Delete => {
                 if let Some(id) = self.get_selected_volume_info() {
-                    self.show_popup = Popup::Delete(id);
+                    let used_by = containers_using(
+                        &Filter::default().filter("volume".to_string(), id.clone()),
+                    )
+                    .await;
+                    // Deleting a resource in use always requires a confirmation
+                    if used_by.is_empty() {
+                        request_confirmation(&tx)?;
+                    }
+                    self.show_popup = Popup::Delete(id, used_by);
                 }
             }
+            Action::Ok if matches!(self.show_popup, Popup::DeleteSelected) => {
+                spawn(delete_selected(
+                    "volumes",
+                    self.selected.drain().collect(),
+                    |id| async move { delete_volume(&id).await },
+                    tx.clone(),
+                ));
+                self.show_popup = Popup::None;
+            }
             Action::Ok => {
-                if let Popup::Delete(id) = &self.show_popup {
+                if let Popup::Delete(id, _) = &self.show_popup {
                     if let Err(e) = delete_volume(id).await {
                         tx.send(Action::Error(format!(
                             "Error deleting volume \"{}\":\n{}",
@@ -228,13 +335,40 @@ impl Volumes {
             .constraints([Constraint::Percentage(100)])
             .split(area);
         let t = table(
-            format!("{}{}", self.get_name(), self.filter.format(),),
-            ["Id", "Driver", "Age"],
-            self.volumes.iter().map(|v| v.into()).collect(),
+            format!(
+                "{}{}{}",
+                self.get_name(),
+                self.filter.format(),
+                self.name_filter
+                    .as_ref()
+                    .map(NameFilter::format)
+                    .unwrap_or_default()
+            ),
+            &["Id", "Driver", "Age"],
+            self.volumes
+                .iter()
+                .map(|v| selection_style(v.into(), self.selected.contains(&v.id)))
+                .collect(),
             &VOLUME_CONSTRAINTS,
             Some(Style::new().gray()),
+            Some(self.sort_by.indicator()),
         );
+        self.page_size = table_page_size(rects[0]);
         f.render_stateful_widget(t, rects[0], &mut self.state);
+        if self.volumes.is_empty() {
+            empty_state(
+                f,
+                rects[0],
+                "volumes",
+                &[
+                    self.filter.to_string(),
+                    self.name_filter
+                        .as_ref()
+                        .map(|f| f.to_string())
+                        .unwrap_or_default(),
+                ],
+            );
+        }
 
         self.draw_popup(f);
     }
@@ -242,6 +376,7 @@ impl Volumes {
     pub(crate) fn get_bindings(&self) -> Option<&[(&str, &str)]> {
         Some(&[
             ("ctrl+d", "Delete"),
+            ("Space", "Select/Unselect"),
             ("i", "Inspect/View details"),
             ("F1", "Sort by volume id"),
             ("F2", "Sort by volume driver"),
@@ -252,6 +387,7 @@ impl Volumes {
     pub(crate) fn get_action(&self, k: &event::KeyEvent) -> Option<Action> {
         match k.code {
             KeyCode::Char('i') => Some(Action::Inspect),
+            KeyCode::Char(' ') => Some(Action::Select),
             _ => None,
         }
     }
@@ -0,0 +1,369 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+    sync::OnceLock,
+    time::Duration,
+};
+
+use color_eyre::Result;
+use eyre::eyre;
+use ratatui::style::Color;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::utils::get_config_dir;
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+const DEFAULT_TICK_RATE: f64 = 2.0;
+const DEFAULT_FRAME_RATE: f64 = 10.0;
+
+const TICK_RATE_BOUNDS: (f64, f64) = (0.1, 20.0);
+const FRAME_RATE_BOUNDS: (f64, f64) = (1.0, 60.0);
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub exec: ExecConfig,
+    pub logs: LogsConfig,
+    pub top: TopConfig,
+    pub ui: UiConfig,
+    pub polling: PollingConfig,
+    pub containers: ContainersConfig,
+    pub logging: LoggingConfig,
+    pub telemetry: TelemetryConfig,
+    // Named filters by resource type (e.g. `containers`), applied as if typed after `/`
+    pub presets: HashMap<String, BTreeMap<String, String>>,
+}
+
+impl Config {
+    pub fn presets(&self, resource: &str) -> Vec<(&str, &str)> {
+        self.presets
+            .get(resource)
+            .map(|p| p.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect())
+            .unwrap_or_default()
+    }
+
+    // Number of ticks to skip between two refreshes of the containers list
+    pub fn list_skipped_ticks(&self, remote: bool) -> usize {
+        if remote {
+            ((self.polling.remote_list_interval * self.ui.tick_rate).round() as usize)
+                .saturating_sub(1)
+        } else {
+            0
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct PollingConfig {
+    // Seconds between two refreshes of the containers CPU/memory metrics
+    pub metrics_interval: f64,
+    // Used instead of the previous one when connected to a remote daemon (HTTP/SSL)
+    pub remote_metrics_interval: f64,
+    // Seconds between two refreshes of the containers list of a remote daemon,
+    // local daemons are refreshed on every tick
+    pub remote_list_interval: f64,
+}
+
+impl Default for PollingConfig {
+    fn default() -> Self {
+        PollingConfig {
+            metrics_interval: 1.0,
+            remote_metrics_interval: 5.0,
+            remote_list_interval: 2.0,
+        }
+    }
+}
+
+impl PollingConfig {
+    pub fn metrics_interval(&self, remote: bool) -> Duration {
+        let interval = if remote {
+            self.remote_metrics_interval
+        } else {
+            self.metrics_interval
+        };
+        Duration::from_secs_f64(interval.max(0.1))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ContainersConfig {
+    // Headers (case insensitive) of the columns hidden from the containers list
+    pub hidden_columns: Vec<String>,
+    // Patterns (case insensitive, `*` matches any characters) of the environment variables
+    // whose values are masked in the container details
+    pub masked_env: Vec<String>,
+    // Show how long running containers have been up instead of their age in the Age column
+    pub uptime: bool,
+}
+
+impl Default for ContainersConfig {
+    fn default() -> Self {
+        ContainersConfig {
+            hidden_columns: vec![],
+            masked_env: ["*PASSWORD*", "*SECRET*", "*TOKEN*", "*KEY*"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            uptime: false,
+        }
+    }
+}
+
+impl ContainersConfig {
+    pub fn is_visible(&self, column: &str) -> bool {
+        !self
+            .hidden_columns
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(column))
+    }
+
+    pub fn is_masked(&self, key: &str) -> bool {
+        let key = key.to_uppercase();
+        self.masked_env
+            .iter()
+            .any(|p| wildcard_matches(&p.to_uppercase(), &key))
+    }
+}
+
+fn wildcard_matches(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = value.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    match parts.split_last() {
+        None => rest.is_empty(),
+        Some((last, middle)) => {
+            for part in middle {
+                match rest.find(part) {
+                    Some(i) => rest = &rest[i + part.len()..],
+                    None => return false,
+                }
+            }
+            rest.ends_with(last)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct LoggingConfig {
+    // Number of daily log files of doggy kept in the data directory
+    pub retention: usize,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        LoggingConfig { retention: 7 }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TelemetryExporter {
+    #[default]
+    Jaeger,
+    Otlp,
+}
+
+// Only used when built with the `otel` feature
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    pub exporter: TelemetryExporter,
+    // Jaeger agent (host:port) or OTLP collector (URL) receiving the traces, when not set
+    // the exporters read their standard `OTEL_EXPORTER_*` environment variables
+    pub endpoint: Option<String>,
+    pub service_name: String,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        TelemetryConfig {
+            exporter: TelemetryExporter::default(),
+            endpoint: None,
+            service_name: env!("CARGO_PKG_NAME").to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct TopConfig {
+    // Arguments passed to `ps` to list the processes of a container
+    pub ps_args: String,
+}
+
+impl Default for TopConfig {
+    fn default() -> Self {
+        TopConfig {
+            ps_args: "aux".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct LogsConfig {
+    // Tokens matched (case insensitive) by the warning/error only filter
+    pub levels: Vec<String>,
+    // Number of lines kept in memory by the logs view, the oldest ones are dropped
+    pub max_lines: usize,
+    // Lines matching a rule are colored, the first matching rule wins
+    pub colors: Vec<LogColorRule>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct LogColorRule {
+    pub pattern: String,
+    // Color name (e.g. `red`, `lightblue`), index (`8`) or hex code (`#ff8800`)
+    pub color: String,
+}
+
+impl LogColorRule {
+    fn new(pattern: &str, color: &str) -> Self {
+        LogColorRule {
+            pattern: pattern.to_string(),
+            color: color.to_string(),
+        }
+    }
+
+    fn parse(&self) -> Result<(Regex, Color)> {
+        let regex = Regex::new(&self.pattern)
+            .map_err(|e| eyre!("Invalid log color pattern {}: {}", self.pattern, e))?;
+        let color = Color::from_str(&self.color)
+            .map_err(|_| eyre!("Invalid log color {} for {}", self.color, self.pattern))?;
+        Ok((regex, color))
+    }
+}
+
+impl Default for LogsConfig {
+    fn default() -> Self {
+        LogsConfig {
+            levels: vec!["ERROR".to_string(), "WARN".to_string(), "FATAL".to_string()],
+            max_lines: 10_000,
+            colors: vec![
+                LogColorRule::new("ERROR|FATAL|PANIC", "red"),
+                LogColorRule::new("WARN", "yellow"),
+                LogColorRule::new("DEBUG|TRACE", "darkgray"),
+            ],
+        }
+    }
+}
+
+impl LogsConfig {
+    pub fn matches(&self, line: &str) -> bool {
+        let line = line.to_uppercase();
+        self.levels.iter().any(|l| line.contains(&l.to_uppercase()))
+    }
+
+    // The rules are checked when the configuration is loaded
+    pub fn color_rules(&self) -> Vec<(Regex, Color)> {
+        self.colors.iter().filter_map(|r| r.parse().ok()).collect()
+    }
+
+    fn validate(&self) -> Result<()> {
+        self.colors.iter().try_for_each(|r| r.parse().map(|_| ()))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct UiConfig {
+    // Duration in seconds before a toast is dismissed
+    pub toast_delay: f64,
+    // Number of data refreshes per second
+    pub tick_rate: f64,
+    // Number of screen redraws per second
+    pub frame_rate: f64,
+    // Ask for a confirmation before deleting or pruning resources
+    pub confirm_destructive: bool,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        UiConfig {
+            toast_delay: 4.0,
+            tick_rate: DEFAULT_TICK_RATE,
+            frame_rate: DEFAULT_FRAME_RATE,
+            confirm_destructive: true,
+        }
+    }
+}
+
+impl UiConfig {
+    fn validate(&self) -> Result<()> {
+        check_bounds("tick_rate", self.tick_rate, TICK_RATE_BOUNDS)?;
+        check_bounds("frame_rate", self.frame_rate, FRAME_RATE_BOUNDS)
+    }
+}
+
+fn check_bounds(name: &str, value: f64, (min, max): (f64, f64)) -> Result<()> {
+    if (min..=max).contains(&value) {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "Invalid {} {}: it should be between {} and {}",
+            name,
+            value,
+            min,
+            max
+        ))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ExecConfig {
+    // Command launched by the shell action
+    pub default_cmd: String,
+    pub env: Vec<String>,
+    pub env_passthrough: Vec<String>,
+}
+
+impl Default for ExecConfig {
+    fn default() -> Self {
+        ExecConfig {
+            default_cmd: "/bin/bash".to_string(),
+            env: vec!["TERM=xterm-256color".to_string()],
+            env_passthrough: vec![],
+        }
+    }
+}
+
+impl ExecConfig {
+    // Configured entries first, then the passed through host variables
+    pub fn env(&self) -> Vec<String> {
+        let passthrough = self
+            .env_passthrough
+            .iter()
+            .filter_map(|name| std::env::var(name).ok().map(|v| format!("{}={}", name, v)));
+        self.env.iter().cloned().chain(passthrough).collect()
+    }
+}
+
+pub fn init(
+    exec_cmd: Option<String>,
+    tick_rate: Option<f64>,
+    frame_rate: Option<f64>,
+) -> Result<()> {
+    let config = config::Config::builder()
+        .add_source(config::File::from(get_config_dir().join("config")).required(false))
+        .set_override_option("exec.default_cmd", exec_cmd)?
+        .set_override_option("ui.tick_rate", tick_rate)?
+        .set_override_option("ui.frame_rate", frame_rate)?
+        .build()?
+        .try_deserialize::<Config>()?;
+    config.ui.validate()?;
+    config.logs.validate()?;
+    CONFIG.set(config).ok();
+    Ok(())
+}
+
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
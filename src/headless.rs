@@ -0,0 +1,132 @@
+use clap::ValueEnum;
+use color_eyre::Result;
+use humansize::{FormatSizeI, BINARY};
+use serde::Serialize;
+
+use crate::runtime::{
+    get_container, get_image, get_network, get_volume, list_containers, list_images, list_networks,
+    list_volumes, Filter,
+};
+use crate::utils::Age;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Resource {
+    #[value(alias = "container")]
+    Containers,
+    #[value(alias = "image")]
+    Images,
+    #[value(alias = "network")]
+    Networks,
+    #[value(alias = "volume")]
+    Volumes,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+}
+
+// Prints every resource of the given kind to stdout, the containers list includes stopped ones
+pub async fn list(resource: Resource, format: Format) -> Result<()> {
+    let filter = Filter::default();
+    match resource {
+        Resource::Containers => {
+            let containers = list_containers(true, &filter).await?;
+            print(
+                format,
+                &containers,
+                &["ID", "NAME", "IMAGE", "STATUS", "AGE"],
+                |c| {
+                    vec![
+                        c.id.to_string(),
+                        c.name.to_string(),
+                        c.image.to_string(),
+                        String::from(c.status.clone()),
+                        c.age.age(),
+                    ]
+                },
+            )
+        }
+        Resource::Images => {
+            let images = list_images(&filter).await?;
+            print(
+                format,
+                &images,
+                &["ID", "NAME", "PLATFORM", "SIZE", "AGE"],
+                |i| {
+                    vec![
+                        i.id.to_string(),
+                        i.name.to_string(),
+                        i.platform(),
+                        i.size.format_size_i(BINARY),
+                        i.created.map(|c| c.age()).unwrap_or("-".into()),
+                    ]
+                },
+            )
+        }
+        Resource::Networks => {
+            let networks = list_networks(&filter).await?;
+            print(format, &networks, &["ID", "NAME", "DRIVER", "AGE"], |n| {
+                vec![
+                    n.id.to_string(),
+                    n.name.to_string(),
+                    n.driver.to_string(),
+                    n.created.age(),
+                ]
+            })
+        }
+        Resource::Volumes => {
+            let volumes = list_volumes(&filter).await?;
+            print(format, &volumes, &["ID", "DRIVER", "AGE"], |v| {
+                vec![v.id.to_string(), v.driver.to_string(), v.created.age()]
+            })
+        }
+    }
+}
+
+// Prints the details of a resource as returned by the runtime inspect endpoints
+pub async fn inspect(resource: Resource, id: &str) -> Result<()> {
+    let details = match resource {
+        Resource::Containers => get_container(id).await?,
+        Resource::Images => get_image(id).await?,
+        Resource::Networks => get_network(id).await?,
+        Resource::Volumes => get_volume(id).await?,
+    };
+    println!("{}", details);
+    Ok(())
+}
+
+fn print<T, F>(format: Format, items: &[T], headers: &[&str], row: F) -> Result<()>
+where
+    T: Serialize,
+    F: Fn(&T) -> Vec<String>,
+{
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(items)?),
+        Format::Table => {
+            let rows: Vec<Vec<String>> = items.iter().map(row).collect();
+            let widths: Vec<usize> = headers
+                .iter()
+                .enumerate()
+                .map(|(i, h)| {
+                    rows.iter()
+                        .map(|r| r[i].chars().count())
+                        .chain([h.len()])
+                        .max()
+                        .unwrap_or_default()
+                })
+                .collect();
+            let headers: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+            for cells in [headers].iter().chain(rows.iter()) {
+                let line: Vec<String> = cells
+                    .iter()
+                    .zip(widths.iter())
+                    .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                    .collect();
+                println!("{}", line.join("  ").trim_end());
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,12 +1,14 @@
 use app::App;
 
-use clap::{arg, command, Parser};
+use clap::{Parser, Subcommand};
 use color_eyre::eyre::Result;
 
 #[cfg(feature = "cri")]
 use eyre::eyre;
 
-use utils::{initialize_logging, initialize_panic_handler, GIT_COMMIT_HASH};
+use utils::{
+    initialize_logging, initialize_panic_handler, initialize_signal_handler, GIT_COMMIT_HASH,
+};
 
 #[cfg(feature = "cri")]
 use runtime::cri;
@@ -15,13 +17,12 @@ use runtime::docker;
 mod action;
 mod app;
 mod components;
+mod config;
+mod headless;
 mod runtime;
 mod tui;
 mod utils;
 
-const DEFAULT_TICK_RATE: f64 = 2.0;
-const DEFAULT_FRAME_RATE: f64 = 10.0;
-
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
@@ -31,17 +32,67 @@ struct Args {
     #[cfg(feature = "cri")]
     #[arg(short, long)]
     cri: Option<String>,
+
+    /// Command launched in containers by the shell action
+    #[arg(long)]
+    exec_cmd: Option<String>,
+
+    /// Number of data refreshes per second
+    #[arg(long)]
+    tick_rate: Option<f64>,
+
+    /// Number of screen redraws per second
+    #[arg(long)]
+    frame_rate: Option<f64>,
+
+    /// Disable the actions modifying containers, images, volumes or networks
+    #[arg(long)]
+    read_only: bool,
+
+    /// Level of the logs written by doggy (error, warn, info, debug or trace),
+    /// overrides the DOGGY_LOGLEVEL and RUST_LOG environment variables
+    #[arg(long)]
+    log_level: Option<tracing::Level>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print the list of a resource and exit without starting the UI
+    List {
+        resource: headless::Resource,
+
+        #[arg(long, default_value = "table")]
+        output: headless::Format,
+    },
+    /// Print the details of a resource as JSON and exit without starting the UI
+    Inspect {
+        resource: headless::Resource,
+
+        id: String,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    initialize_logging()?;
+    let mut args = Args::parse();
+
+    config::init(args.exec_cmd, args.tick_rate, args.frame_rate)?;
+
+    initialize_logging(args.log_level)?;
 
     initialize_panic_handler()?;
 
+    initialize_signal_handler()?;
+
+    let read_only = args.read_only;
+    let command = args.command.take();
+
     #[cfg(feature = "cri")]
     let config = {
-        let Args { docker, cri } = Args::parse();
+        let Args { docker, cri, .. } = args;
         match (docker, cri) {
             (Some(docker), None) => Some(runtime::ConnectionConfig::Docker(
                 docker::ConnectionConfig::socket(docker),
@@ -56,15 +107,51 @@ async fn main() -> Result<()> {
 
     #[cfg(not(feature = "cri"))]
     let config = {
-        let Args { docker } = Args::parse();
+        let Args { docker, .. } = args;
         docker.map(|d| runtime::ConnectionConfig::Docker(docker::ConnectionConfig::socket(d)))
     };
 
-    runtime::init(config).await?;
+    let configs = match runtime::detect_configs(config) {
+        Ok(configs) => configs,
+        Err(e) => {
+            // Not a bug but a setup issue, the report would only add noise
+            eprintln!("{}", e);
+            std::process::exit(libc::EXIT_FAILURE);
+        }
+    };
+
+    if let Some(command) = command {
+        // Without the UI to choose, the first detected runtime is used
+        runtime::connect(configs[0].clone()).await?;
+        let result = match command {
+            Command::List { resource, output } => headless::list(resource, output).await,
+            Command::Inspect { resource, id } => headless::inspect(resource, &id).await,
+        };
+        runtime::shutdown().await;
+        return result;
+    }
+
+    // ssh may prompt for a password or a passphrase, it must be answered before the UI starts,
+    // other connections are established behind the UI connecting screen. When several
+    // runtimes are detected the UI lets the user choose first.
+    let connected = configs.len() == 1 && configs[0].is_interactive();
+    if connected {
+        runtime::connect(configs[0].clone()).await?;
+    }
 
     // create app and run it
-    let mut app = App::new(GIT_COMMIT_HASH, DEFAULT_TICK_RATE, DEFAULT_FRAME_RATE);
-    if let Err(e) = app.run().await {
+    let ui = &config::get().ui;
+    let mut app = App::new(
+        GIT_COMMIT_HASH,
+        ui.tick_rate,
+        ui.frame_rate,
+        read_only,
+        configs,
+        connected,
+    );
+    let result = app.run().await;
+    runtime::shutdown().await;
+    if let Err(e) = result {
         eprintln!("{} error: Something went wrong", env!("CARGO_PKG_NAME"));
         Err(e)
     } else {
@@ -1,6 +1,11 @@
 use std::fmt::Display;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Duration;
 
 use tokio::sync::Mutex;
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
 
 use lazy_static::lazy_static;
 
@@ -19,6 +24,56 @@ pub use model::*;
 
 lazy_static! {
     static ref CLIENT: Mutex<Option<Connection>> = Mutex::new(None);
+    static ref RUNTIME_INFO: Mutex<Option<RuntimeSummary>> = Mutex::new(None);
+}
+
+// The runtime name and version rarely change, they are refreshed in the background
+const RUNTIME_INFO_INTERVAL: Duration = Duration::from_secs(30);
+// Delay before fetching them again after a failure, doubled on each consecutive failure
+const RUNTIME_INFO_RETRY: Duration = Duration::from_secs(2);
+const RUNTIME_INFO_MAX_RETRY: Duration = Duration::from_secs(300);
+
+// Attempts of the read only operations failing with a transient (connection) error
+const RETRIES: u32 = 3;
+const RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+fn is_transient(error: &eyre::Report) -> bool {
+    if let Some(e) = error.downcast_ref::<bollard::errors::Error>() {
+        return match e {
+            bollard::errors::Error::IOError { .. }
+            | bollard::errors::Error::HyperResponseError { .. }
+            | bollard::errors::Error::RequestTimeoutError => true,
+            bollard::errors::Error::DockerResponseServerError { status_code, .. } => {
+                *status_code >= 500
+            }
+            _ => false,
+        };
+    }
+    #[cfg(feature = "cri")]
+    if let Some(status) = error.downcast_ref::<tonic::Status>() {
+        return status.code() == tonic::Code::Unavailable;
+    }
+    error.downcast_ref::<std::io::Error>().is_some()
+}
+
+// Retries an idempotent read with an increasing delay, it must not be used for operations
+// modifying the runtime state which could be applied twice
+async fn with_retry<T, F, Fut>(operation: F) -> Result<T>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 1;
+    loop {
+        match operation().await {
+            Err(e) if attempt < RETRIES && is_transient(&e) => {
+                tracing::debug!("Retrying after a transient error: {}", e);
+                sleep(RETRY_BACKOFF * attempt).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
 }
 
 pub const CONTAINERS: &str = "containers";
@@ -26,13 +81,14 @@ pub const COMPOSES: &str = "composes";
 pub const IMAGES: &str = "images";
 pub const NETWORKS: &str = "networks";
 pub const VOLUMES: &str = "volumes";
+pub const EVENTS: &str = "events";
 
 pub(crate) async fn get_suggestions() -> &'static [&'static str] {
     let client = CLIENT.lock().await;
     match *client {
         Some(ref conn) => match &conn.client {
             #[cfg(feature = "docker")]
-            Client::Docker(_) => &[CONTAINERS, COMPOSES, IMAGES, NETWORKS, VOLUMES],
+            Client::Docker(_) => &[CONTAINERS, COMPOSES, IMAGES, NETWORKS, VOLUMES, EVENTS],
             #[cfg(feature = "cri")]
             Client::Cri(_) => &[CONTAINERS, IMAGES],
         },
@@ -58,13 +114,41 @@ impl Display for ConnectionConfig {
     }
 }
 
+impl ConnectionConfig {
+    pub fn is_remote(&self) -> bool {
+        match self {
+            ConnectionConfig::Docker(config) => config.is_remote(),
+            #[cfg(feature = "cri")]
+            ConnectionConfig::Cri(_) => false,
+        }
+    }
+
+    pub fn runtime_name(&self) -> &'static str {
+        match self {
+            ConnectionConfig::Docker(_) => "Docker",
+            #[cfg(feature = "cri")]
+            ConnectionConfig::Cri(_) => "CRI",
+        }
+    }
+
+    // The connection may prompt the user (e.g. a ssh password) so it can't happen in the UI
+    pub fn is_interactive(&self) -> bool {
+        match self {
+            ConnectionConfig::Docker(config) => config.is_interactive(),
+            #[cfg(feature = "cri")]
+            ConnectionConfig::Cri(_) => false,
+        }
+    }
+}
+
 #[allow(dead_code)]
 struct Connection {
     config: ConnectionConfig,
     client: Client,
 }
 
-#[allow(dead_code)]
+#[allow(dead_code, clippy::large_enum_variant)]
+#[derive(Clone)]
 pub enum Client {
     #[cfg(feature = "docker")]
     Docker(docker::Client),
@@ -104,57 +188,87 @@ async fn init_cri(config: cri::ConnectionConfig) -> Result<()> {
     Ok(())
 }
 
-pub async fn init(config: Option<ConnectionConfig>) -> Result<()> {
-    #[cfg(feature = "cri")]
-    {
-        let config = config
-            .or_else(|| docker::detect_connection_config().map(ConnectionConfig::Docker))
-            .or_else(|| cri::detect_connection_config().map(ConnectionConfig::Cri));
-
-        match config {
-            Some(ConnectionConfig::Cri(c)) => init_cri(c).await,
-            Some(ConnectionConfig::Docker(c)) => init_docker(c).await,
-            None => Err(eyre!("No configuration found for runtime")),
-        }
+// Configuration given on the command line, or every runtime detected from the environment
+pub fn detect_configs(config: Option<ConnectionConfig>) -> Result<Vec<ConnectionConfig>> {
+    if let Some(config) = config {
+        return Ok(vec![config]);
+    }
+    let configs: Vec<ConnectionConfig> = [
+        docker::detect_connection_config().map(ConnectionConfig::Docker),
+        #[cfg(feature = "cri")]
+        cri::detect_connection_config().map(ConnectionConfig::Cri),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if configs.is_empty() {
+        Err(no_runtime_error())
+    } else {
+        Ok(configs)
     }
+}
 
+fn no_runtime_error() -> eyre::Report {
+    let probed = docker::probed_sockets();
+    #[cfg(feature = "cri")]
+    let probed = [probed, cri::probed_sockets()].concat();
+    #[cfg(feature = "cri")]
+    let options = "`--docker <socket path>`, `--cri <socket path>`";
     #[cfg(not(feature = "cri"))]
-    {
-        let config =
-            config.or_else(|| docker::detect_connection_config().map(ConnectionConfig::Docker));
+    let options = "`--docker <socket path>`";
+    eyre!(
+        "No container runtime found, none of these sockets exists:\n{}\n\n\
+        Start your runtime (Docker, Podman, Colima...) or give its address with {} \
+        or the DOCKER_HOST environment variable (e.g. unix:///path/to/docker.sock, \
+        tcp://host:2375 or ssh://user@host)",
+        probed
+            .iter()
+            .map(|s| format!("  - {}", s))
+            .collect::<Vec<String>>()
+            .join("\n"),
+        options
+    )
+}
 
-        match config {
-            Some(ConnectionConfig::Docker(c)) => init_docker(c).await,
-            None => Err(eyre!("No configuration found for runtime")),
-        }
+pub async fn connect(config: ConnectionConfig) -> Result<()> {
+    match config {
+        ConnectionConfig::Docker(c) => init_docker(c).await,
+        #[cfg(feature = "cri")]
+        ConnectionConfig::Cri(c) => init_cri(c).await,
     }
 }
 
 pub(crate) async fn list_volumes(filter: &Filter) -> Result<Vec<VolumeSummary>> {
-    let client = CLIENT.lock().await;
-    match *client {
-        Some(ref conn) => match &conn.client {
-            #[cfg(feature = "docker")]
-            Client::Docker(client) => client.list_volumes(filter).await,
-            #[cfg(feature = "cri")]
-            _ => unimplemented!(),
-        },
-        _ => Err(eyre!("Not initialized")),
-    }
+    with_retry(|| async move {
+        let client = CLIENT.lock().await;
+        match *client {
+            Some(ref conn) => match &conn.client {
+                #[cfg(feature = "docker")]
+                Client::Docker(client) => client.list_volumes(filter).await,
+                #[cfg(feature = "cri")]
+                _ => unimplemented!(),
+            },
+            _ => Err(eyre!("Not initialized")),
+        }
+    })
+    .await
 }
 
 #[allow(dead_code)]
 pub(crate) async fn get_volume(id: &str) -> Result<String> {
-    let client = CLIENT.lock().await;
-    match *client {
-        Some(ref conn) => match &conn.client {
-            #[cfg(feature = "docker")]
-            Client::Docker(client) => client.get_volume(id).await,
-            #[cfg(feature = "cri")]
-            _ => unimplemented!(),
-        },
-        _ => Err(eyre!("Not initialized")),
-    }
+    with_retry(|| async move {
+        let client = CLIENT.lock().await;
+        match *client {
+            Some(ref conn) => match &conn.client {
+                #[cfg(feature = "docker")]
+                Client::Docker(client) => client.get_volume(id).await,
+                #[cfg(feature = "cri")]
+                _ => unimplemented!(),
+            },
+            _ => Err(eyre!("Not initialized")),
+        }
+    })
+    .await
 }
 
 pub(crate) async fn delete_volume(id: &str) -> Result<()> {
@@ -171,29 +285,35 @@ pub(crate) async fn delete_volume(id: &str) -> Result<()> {
 }
 
 pub(crate) async fn list_networks(filter: &Filter) -> Result<Vec<NetworkSummary>> {
-    let client = CLIENT.lock().await;
-    match *client {
-        Some(ref conn) => match &conn.client {
-            #[cfg(feature = "docker")]
-            Client::Docker(client) => client.list_networks(filter).await,
-            #[cfg(feature = "cri")]
-            _ => unimplemented!(),
-        },
-        _ => Err(eyre!("Not initialized")),
-    }
+    with_retry(|| async move {
+        let client = CLIENT.lock().await;
+        match *client {
+            Some(ref conn) => match &conn.client {
+                #[cfg(feature = "docker")]
+                Client::Docker(client) => client.list_networks(filter).await,
+                #[cfg(feature = "cri")]
+                _ => unimplemented!(),
+            },
+            _ => Err(eyre!("Not initialized")),
+        }
+    })
+    .await
 }
 
 pub(crate) async fn get_network(id: &str) -> Result<String> {
-    let client = CLIENT.lock().await;
-    match *client {
-        Some(ref conn) => match &conn.client {
-            #[cfg(feature = "docker")]
-            Client::Docker(client) => client.get_network(id).await,
-            #[cfg(feature = "cri")]
-            _ => unimplemented!(),
-        },
-        _ => Err(eyre!("Not initialized")),
-    }
+    with_retry(|| async move {
+        let client = CLIENT.lock().await;
+        match *client {
+            Some(ref conn) => match &conn.client {
+                #[cfg(feature = "docker")]
+                Client::Docker(client) => client.get_network(id).await,
+                #[cfg(feature = "cri")]
+                _ => unimplemented!(),
+            },
+            _ => Err(eyre!("Not initialized")),
+        }
+    })
+    .await
 }
 
 pub(crate) async fn delete_network(id: &str) -> Result<()> {
@@ -209,30 +329,36 @@ pub(crate) async fn delete_network(id: &str) -> Result<()> {
     }
 }
 
-pub(crate) async fn list_images(filter: &Option<String>) -> Result<Vec<ImageSummary>> {
-    let mut client = CLIENT.lock().await;
-    match *client {
-        Some(ref mut conn) => match &mut conn.client {
-            #[cfg(feature = "docker")]
-            Client::Docker(client) => client.list_images(filter).await,
-            #[cfg(feature = "cri")]
-            Client::Cri(ref mut client) => client.list_images(filter).await,
-        },
-        _ => Err(eyre!("Not initialized")),
-    }
+pub(crate) async fn list_images(filter: &Filter) -> Result<Vec<ImageSummary>> {
+    with_retry(|| async move {
+        let mut client = CLIENT.lock().await;
+        match *client {
+            Some(ref mut conn) => match &mut conn.client {
+                #[cfg(feature = "docker")]
+                Client::Docker(client) => client.list_images(filter).await,
+                #[cfg(feature = "cri")]
+                Client::Cri(ref mut client) => client.list_images(filter).await,
+            },
+            _ => Err(eyre!("Not initialized")),
+        }
+    })
+    .await
 }
 
 pub(crate) async fn get_image(id: &str) -> Result<String> {
-    let mut client = CLIENT.lock().await;
-    match *client {
-        Some(ref mut conn) => match &mut conn.client {
-            #[cfg(feature = "docker")]
-            Client::Docker(client) => client.get_image(id).await,
-            #[cfg(feature = "cri")]
-            Client::Cri(ref mut client) => client.get_image(id).await,
-        },
-        _ => Err(eyre!("Not initialized")),
-    }
+    with_retry(|| async move {
+        let mut client = CLIENT.lock().await;
+        match *client {
+            Some(ref mut conn) => match &mut conn.client {
+                #[cfg(feature = "docker")]
+                Client::Docker(client) => client.get_image(id).await,
+                #[cfg(feature = "cri")]
+                Client::Cri(ref mut client) => client.get_image(id).await,
+            },
+            _ => Err(eyre!("Not initialized")),
+        }
+    })
+    .await
 }
 
 pub(crate) async fn delete_image(id: &str) -> Result<()> {
@@ -261,43 +387,164 @@ pub(crate) async fn delete_container(cid: &str) -> Result<()> {
     }
 }
 
-pub(crate) async fn list_containers(all: bool, filter: &Filter) -> Result<Vec<ContainerSummary>> {
-    let mut client = CLIENT.lock().await;
-    match *client {
-        Some(ref mut conn) => match &mut conn.client {
-            #[cfg(feature = "docker")]
-            Client::Docker(client) => client.list_containers(all, filter).await,
-            #[cfg(feature = "cri")]
-            Client::Cri(client) => client.list_containers(all, filter).await,
-        },
-        _ => Err(eyre!("Not initialized")),
+pub(crate) async fn stop_container(cid: &str) -> Result<()> {
+    // Stopping lasts up to the grace period of the container, the lock must not be held meanwhile
+    let client = match *CLIENT.lock().await {
+        Some(ref conn) => conn.client.clone(),
+        _ => Err(eyre!("Not initialized"))?,
+    };
+    match client {
+        #[cfg(feature = "docker")]
+        Client::Docker(client) => client.stop_container(cid).await,
+        #[cfg(feature = "cri")]
+        Client::Cri(mut client) => client.stop_container(cid).await,
     }
 }
 
-pub(crate) async fn get_container(cid: &str) -> Result<String> {
-    let mut client = CLIENT.lock().await;
-    match *client {
-        Some(ref mut conn) => match &mut conn.client {
+pub(crate) async fn wait_container(cid: &str) -> Result<i64> {
+    // Waiting can take forever, the lock must not be held meanwhile
+    let client = match *CLIENT.lock().await {
+        Some(ref conn) => match &conn.client {
             #[cfg(feature = "docker")]
-            Client::Docker(client) => client.get_container(cid).await,
+            Client::Docker(client) => client.clone(),
             #[cfg(feature = "cri")]
-            Client::Cri(client) => client.get_container(cid).await,
+            _ => Err(eyre!(
+                "Waiting for containers is not supported by the CRI runtime"
+            ))?,
         },
-        _ => Err(eyre!("Not initialized")),
-    }
+        _ => Err(eyre!("Not initialized"))?,
+    };
+    client.wait_container(cid).await
+}
+
+pub(crate) async fn list_containers(all: bool, filter: &Filter) -> Result<Vec<ContainerSummary>> {
+    with_retry(|| async move {
+        let mut client = CLIENT.lock().await;
+        match *client {
+            Some(ref mut conn) => match &mut conn.client {
+                #[cfg(feature = "docker")]
+                Client::Docker(client) => client.list_containers(all, filter).await,
+                #[cfg(feature = "cri")]
+                Client::Cri(client) => client.list_containers(all, filter).await,
+            },
+            _ => Err(eyre!("Not initialized")),
+        }
+    })
+    .await
+}
+
+pub(crate) async fn get_container(cid: &str) -> Result<String> {
+    with_retry(|| async move {
+        let mut client = CLIENT.lock().await;
+        match *client {
+            Some(ref mut conn) => match &mut conn.client {
+                #[cfg(feature = "docker")]
+                Client::Docker(client) => client.get_container(cid).await,
+                #[cfg(feature = "cri")]
+                Client::Cri(client) => client.get_container(cid).await,
+            },
+            _ => Err(eyre!("Not initialized")),
+        }
+    })
+    .await
 }
 
 pub(crate) async fn get_container_details(cid: &str) -> Result<ContainerDetails> {
-    let mut client = CLIENT.lock().await;
-    match *client {
-        Some(ref mut conn) => match &mut conn.client {
+    with_retry(|| async move {
+        let mut client = CLIENT.lock().await;
+        match *client {
+            Some(ref mut conn) => match &mut conn.client {
+                #[cfg(feature = "docker")]
+                Client::Docker(client) => client.get_container_details(cid.to_string()).await,
+                #[cfg(feature = "cri")]
+                Client::Cri(_client) => unimplemented!(),
+            },
+            _ => Err(eyre!("Not initialized")),
+        }
+    })
+    .await
+}
+
+pub(crate) async fn get_container_restart_count(cid: &str) -> Result<i64> {
+    with_retry(|| async move {
+        let mut client = CLIENT.lock().await;
+        match *client {
+            Some(ref mut conn) => match &mut conn.client {
+                #[cfg(feature = "docker")]
+                Client::Docker(client) => client.get_container_restart_count(cid).await,
+                #[cfg(feature = "cri")]
+                Client::Cri(client) => client.get_container_restart_count(cid).await,
+            },
+            _ => Err(eyre!("Not initialized")),
+        }
+    })
+    .await
+}
+
+pub(crate) async fn get_container_started_at(cid: &str) -> Result<Option<i64>> {
+    with_retry(|| async move {
+        let mut client = CLIENT.lock().await;
+        match *client {
+            Some(ref mut conn) => match &mut conn.client {
+                #[cfg(feature = "docker")]
+                Client::Docker(client) => client.get_container_started_at(cid).await,
+                #[cfg(feature = "cri")]
+                Client::Cri(client) => client.get_container_started_at(cid).await,
+            },
+            _ => Err(eyre!("Not initialized")),
+        }
+    })
+    .await
+}
+
+pub(crate) async fn get_container_processes(cid: &str) -> Result<Vec<Process>> {
+    with_retry(|| async move {
+        let client = CLIENT.lock().await;
+        match *client {
+            Some(ref conn) => match &conn.client {
+                #[cfg(feature = "docker")]
+                Client::Docker(client) => client.get_container_processes(cid).await,
+                #[cfg(feature = "cri")]
+                _ => unimplemented!(),
+            },
+            _ => Err(eyre!("Not initialized")),
+        }
+    })
+    .await
+}
+
+pub(crate) async fn get_container_changes(cid: &str) -> Result<Vec<FileChange>> {
+    with_retry(|| async move {
+        let client = CLIENT.lock().await;
+        match *client {
+            Some(ref conn) => match &conn.client {
+                #[cfg(feature = "docker")]
+                Client::Docker(client) => client.container_changes(cid).await,
+                #[cfg(feature = "cri")]
+                _ => unimplemented!(),
+            },
+            _ => Err(eyre!("Not initialized")),
+        }
+    })
+    .await
+}
+
+pub(crate) async fn download_from_container(
+    cid: &str,
+    path: &str,
+    dest: PathBuf,
+) -> Result<PathBuf> {
+    // The client is cloned so the transfer does not hold the lock
+    let client = match *CLIENT.lock().await {
+        Some(ref conn) => match &conn.client {
             #[cfg(feature = "docker")]
-            Client::Docker(client) => client.get_container_details(cid.to_string()).await,
+            Client::Docker(client) => client.clone(),
             #[cfg(feature = "cri")]
-            Client::Cri(_client) => unimplemented!(),
+            _ => unimplemented!(),
         },
-        _ => Err(eyre!("Not initialized")),
-    }
+        _ => Err(eyre!("Not initialized"))?,
+    };
+    client.download_from_container(cid, path, dest).await
 }
 
 pub(crate) async fn get_container_logs(
@@ -332,25 +579,85 @@ pub(crate) async fn get_container_stats(
     }
 }
 
-pub(crate) async fn container_exec(cid: &str, cmd: &str) -> Result<()> {
+pub(crate) async fn get_events() -> Result<impl Stream<Item = Result<Event>>> {
     let client = CLIENT.lock().await;
     match *client {
         Some(ref conn) => match &conn.client {
             #[cfg(feature = "docker")]
-            Client::Docker(client) => client.container_exec(cid, cmd).await,
+            Client::Docker(client) => client.get_events(),
             #[cfg(feature = "cri")]
-            _ => unimplemented!(),
+            _ => Err(eyre!("Events are not supported by the CRI runtime")),
         },
         _ => Err(eyre!("Not initialized")),
     }
 }
 
-pub(crate) async fn list_compose_projects() -> Result<Vec<Compose>> {
+pub(crate) async fn pull_image(
+    reference: &str,
+    credentials: Option<RegistryCredentials>,
+) -> Result<impl Stream<Item = Result<String>>> {
+    // The client is cloned so the lock is not held while the credentials are looked up
+    let client = match *CLIENT.lock().await {
+        Some(ref conn) => match &conn.client {
+            #[cfg(feature = "docker")]
+            Client::Docker(client) => client.clone(),
+            #[cfg(feature = "cri")]
+            _ => Err(eyre!("Pulling images is not supported by the CRI runtime"))?,
+        },
+        _ => Err(eyre!("Not initialized"))?,
+    };
+    client.pull_image(reference, credentials).await
+}
+
+pub(crate) async fn save_image(id: &str, dest: PathBuf) -> Result<u64> {
+    // The client is cloned so the export does not hold the lock while streaming
+    let client = match *CLIENT.lock().await {
+        Some(ref conn) => match &conn.client {
+            #[cfg(feature = "docker")]
+            Client::Docker(client) => client.clone(),
+            #[cfg(feature = "cri")]
+            _ => Err(eyre!("Saving images is not supported by the CRI runtime"))?,
+        },
+        _ => Err(eyre!("Not initialized"))?,
+    };
+    client.save_image(id, dest).await
+}
+
+pub(crate) async fn load_image(src: PathBuf) -> Result<impl Stream<Item = Result<String>>> {
+    let client = CLIENT.lock().await;
+    match *client {
+        Some(ref conn) => match &conn.client {
+            #[cfg(feature = "docker")]
+            Client::Docker(client) => client.load_image(src).await,
+            #[cfg(feature = "cri")]
+            _ => Err(eyre!("Loading images is not supported by the CRI runtime")),
+        },
+        _ => Err(eyre!("Not initialized")),
+    }
+}
+
+pub(crate) async fn commit_container(cid: &str, repo: &str, tag: &str) -> Result<()> {
+    // The container is paused while committed, the lock is not held meanwhile
+    let client = match *CLIENT.lock().await {
+        Some(ref conn) => match &conn.client {
+            #[cfg(feature = "docker")]
+            Client::Docker(client) => client.clone(),
+            #[cfg(feature = "cri")]
+            _ => Err(eyre!(
+                "Committing containers is not supported by the CRI runtime"
+            ))?,
+        },
+        _ => Err(eyre!("Not initialized"))?,
+    };
+    client.commit_container(cid, repo, tag).await
+}
+
+pub(crate) async fn container_exec(cid: &str, options: &ExecOptions) -> Result<ExecResult> {
     let client = CLIENT.lock().await;
     match *client {
         Some(ref conn) => match &conn.client {
             #[cfg(feature = "docker")]
-            Client::Docker(client) => client.list_compose_projects().await,
+            Client::Docker(client) => client.container_exec(cid, options).await,
             #[cfg(feature = "cri")]
             _ => unimplemented!(),
         },
@@ -358,7 +665,70 @@ pub(crate) async fn list_compose_projects() -> Result<Vec<Compose>> {
     }
 }
 
+pub(crate) async fn list_compose_projects() -> Result<Vec<Compose>> {
+    with_retry(|| async move {
+        let client = CLIENT.lock().await;
+        match *client {
+            Some(ref conn) => match &conn.client {
+                #[cfg(feature = "docker")]
+                Client::Docker(client) => client.list_compose_projects().await,
+                #[cfg(feature = "cri")]
+                _ => unimplemented!(),
+            },
+            _ => Err(eyre!("Not initialized")),
+        }
+    })
+    .await
+}
+
+// Drops the connection, closing the ssh tunnel if any
+pub async fn shutdown() {
+    RUNTIME_INFO.lock().await.take();
+    CLIENT.lock().await.take();
+}
+
+pub(crate) async fn is_remote() -> bool {
+    match *CLIENT.lock().await {
+        Some(ref conn) => conn.config.is_remote(),
+        None => false,
+    }
+}
+
+// Fetches the runtime info and caches it for `cached_runtime_info`
 pub(crate) async fn get_runtime_info() -> Result<RuntimeSummary> {
+    let info = fetch_runtime_info().await?;
+    *RUNTIME_INFO.lock().await = Some(info.clone());
+    Ok(info)
+}
+
+// Last fetched runtime info, it never waits for the runtime
+pub(crate) async fn cached_runtime_info() -> Option<RuntimeSummary> {
+    RUNTIME_INFO.lock().await.clone()
+}
+
+// Keeps the cached runtime info up to date, backing off while the runtime is unreachable
+pub(crate) async fn refresh_runtime_info(cancel: CancellationToken) {
+    let mut delay = RUNTIME_INFO_INTERVAL;
+    let mut failing = false;
+    loop {
+        tokio::select! {
+            _ = sleep(delay) => {}
+            _ = cancel.cancelled() => return,
+        }
+        (delay, failing) = match get_runtime_info().await {
+            Ok(_) => (RUNTIME_INFO_INTERVAL, false),
+            Err(e) => {
+                log::debug!("Unable to refresh the runtime info: {}", e);
+                match failing {
+                    true => ((delay * 2).min(RUNTIME_INFO_MAX_RETRY), true),
+                    false => (RUNTIME_INFO_RETRY, true),
+                }
+            }
+        };
+    }
+}
+
+async fn fetch_runtime_info() -> Result<RuntimeSummary> {
     let mut client = CLIENT.lock().await;
     let (name, version) = match *client {
         Some(ref mut conn) => match &mut conn.client {
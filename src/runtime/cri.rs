@@ -1,21 +1,31 @@
-use std::{collections::HashMap, fmt::Display, fs};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs,
+    sync::{Arc, Mutex},
+};
 
+use chrono::DateTime;
 use color_eyre::Result;
 
 use k8s_cri::v1::{
     image_service_client::ImageServiceClient, runtime_service_client::RuntimeServiceClient,
     ContainerStatusRequest, ImageSpec, ImageStatusRequest, ListContainersRequest,
-    ListImagesRequest, RemoveContainerRequest, RemoveImageRequest, VersionRequest,
+    ListImagesRequest, RemoveContainerRequest, RemoveImageRequest, StopContainerRequest,
+    VersionRequest,
 };
 
 use tokio::net::UnixStream;
 use tonic::transport::{Channel, Endpoint, Uri};
 use tower::service_fn;
 
-use super::{ContainerSummary, ImageSummary};
+use super::{container_name, ContainerSummary, Filter, ImageSummary};
 
 const DEFAULT_SOCKET_PATH: &str = "/run/containerd/containerd.sock";
 
+// Seconds given to the container to stop before it's killed, like `docker stop`
+const STOP_TIMEOUT: i64 = 10;
+
 enum ContainerState {
     Created = 0,
     Running = 1,
@@ -38,7 +48,9 @@ impl From<ContainerState> for super::ContainerStatus {
     fn from(value: ContainerState) -> Self {
         match value {
             ContainerState::Created => super::ContainerStatus::Created,
-            ContainerState::Running => super::ContainerStatus::Running,
+            ContainerState::Running => {
+                super::ContainerStatus::Running(super::ContainerHealth::Unknown)
+            }
             ContainerState::Exited => super::ContainerStatus::Exited,
             _ => super::ContainerStatus::Unknown,
         }
@@ -73,9 +85,16 @@ impl Display for ConnectionConfig {
     }
 }
 
+#[derive(Clone)]
 pub struct Client {
     image_client: ImageServiceClient<Channel>,
     runtime_client: RuntimeServiceClient<Channel>,
+    // Creation dates by image id, an image content never changes so they are fetched once
+    created: Arc<Mutex<HashMap<String, Option<i64>>>>,
+}
+
+pub fn probed_sockets() -> Vec<String> {
+    vec![DEFAULT_SOCKET_PATH.to_string()]
 }
 
 pub fn detect_connection_config() -> Option<ConnectionConfig> {
@@ -106,34 +125,55 @@ pub(crate) async fn connect(config: &ConnectionConfig) -> Result<Client> {
     Ok(Client {
         image_client,
         runtime_client,
+        created: Default::default(),
     })
 }
 
 impl Client {
-    pub(crate) async fn list_images(
-        &mut self,
-        _filter: &Option<String>,
-    ) -> Result<Vec<ImageSummary>> {
+    pub(crate) async fn list_images(&mut self, _filter: &Filter) -> Result<Vec<ImageSummary>> {
         let request = tonic::Request::new(ListImagesRequest { filter: None });
         let response = self.image_client.list_images(request).await?;
-        let images = response
-            .get_ref()
-            .images
-            .iter()
-            .map(|i| ImageSummary {
+        let mut images = Vec::new();
+        for i in response.get_ref().images.iter() {
+            // The creation date is only exposed in the verbose image status
+            let created = self.get_image_created(&i.id).await;
+            images.push(ImageSummary {
                 id: i.id.split(':').nth(1).unwrap_or("<Unknown>").to_string(),
                 name: i
                     .repo_tags
                     .first()
                     .cloned()
                     .unwrap_or("<Unknown>".to_string()),
-                size: i.size as i64,
-                created: 0,
-            })
-            .collect();
+                size: i64::try_from(i.size).unwrap_or(i64::MAX),
+                created,
+                architecture: String::new(),
+                os: String::new(),
+            });
+        }
         Ok(images)
     }
 
+    async fn get_image_created(&mut self, id: &str) -> Option<i64> {
+        if let Some(created) = self.created.lock().ok().and_then(|c| c.get(id).copied()) {
+            return created;
+        }
+        let spec = ImageSpec {
+            image: id.to_string(),
+            annotations: HashMap::new(),
+        };
+        let request = tonic::Request::new(ImageStatusRequest {
+            image: Some(spec),
+            verbose: true,
+        });
+        // Failed requests are not cached, they are tried again on the next refresh
+        let response = self.image_client.image_status(request).await.ok()?;
+        let created = parse_image_created(&response.get_ref().info);
+        if let Ok(mut cache) = self.created.lock() {
+            cache.insert(id.to_string(), created);
+        }
+        created
+    }
+
     pub(crate) async fn get_image(&mut self, id: &str) -> Result<String> {
         let spec = ImageSpec {
             image: id.to_string(),
@@ -145,7 +185,7 @@ impl Client {
         });
         let response = self.image_client.image_status(request).await?;
         let image_status = response.get_ref();
-        Ok(format!("{:?}", image_status))
+        Ok(serde_json::to_string_pretty(image_status)?)
     }
 
     pub(crate) async fn delete_image(&mut self, id: &str) -> Result<()> {
@@ -166,10 +206,19 @@ impl Client {
         Ok(())
     }
 
+    pub(crate) async fn stop_container(&mut self, cid: &str) -> Result<()> {
+        let request = tonic::Request::new(StopContainerRequest {
+            container_id: cid.to_string(),
+            timeout: STOP_TIMEOUT,
+        });
+        let _response = self.runtime_client.stop_container(request).await?;
+        Ok(())
+    }
+
     pub(crate) async fn list_containers(
         &mut self,
         _all: bool,
-        _filter: &Option<String>,
+        _filter: &Filter,
     ) -> Result<Vec<ContainerSummary>> {
         let request = tonic::Request::new(ListContainersRequest { filter: None });
         let response = self.runtime_client.list_containers(request).await?;
@@ -181,18 +230,16 @@ impl Client {
                 let state: ContainerState = c.state.into();
                 ContainerSummary {
                     id: c.id.to_string(),
-                    name: c
-                        .metadata
-                        .clone()
-                        .map(|m| m.name)
-                        .unwrap_or("<Unknown>".to_string()),
+                    name: container_name(c.metadata.as_ref().map(|m| m.name.as_str()), &c.id),
                     image: c
                         .image
                         .clone()
                         .map(|i| i.image)
                         .unwrap_or("<Unknown>".to_string()),
                     image_id: c.image_ref.to_string(),
+                    labels: c.labels.clone(),
                     age: c.created_at,
+                    started: None,
                     status: state.into(),
                 }
             })
@@ -207,7 +254,38 @@ impl Client {
         });
         let response = self.runtime_client.container_status(request).await?;
         let container_status = response.get_ref();
-        Ok(format!("{:?}", container_status))
+        Ok(serde_json::to_string_pretty(container_status)?)
+    }
+
+    // Kubelet recreates the container on restart, the attempt is the number of previous ones
+    pub(crate) async fn get_container_restart_count(&mut self, cid: &str) -> Result<i64> {
+        let request = tonic::Request::new(ContainerStatusRequest {
+            container_id: cid.to_string(),
+            verbose: false,
+        });
+        let response = self.runtime_client.container_status(request).await?;
+        Ok(response
+            .get_ref()
+            .status
+            .as_ref()
+            .and_then(|s| s.metadata.as_ref())
+            .map(|m| i64::from(m.attempt))
+            .unwrap_or_default())
+    }
+
+    pub(crate) async fn get_container_started_at(&mut self, cid: &str) -> Result<Option<i64>> {
+        let request = tonic::Request::new(ContainerStatusRequest {
+            container_id: cid.to_string(),
+            verbose: false,
+        });
+        let response = self.runtime_client.container_status(request).await?;
+        // Nanoseconds, zero when the container was never started
+        Ok(response
+            .get_ref()
+            .status
+            .as_ref()
+            .map(|s| s.started_at / 1_000_000_000)
+            .filter(|t| *t > 0))
     }
 
     /*
@@ -240,3 +318,11 @@ impl Client {
         true
     }
 }
+
+fn parse_image_created(info: &HashMap<String, String>) -> Option<i64> {
+    let info: serde_json::Value = serde_json::from_str(info.get("info")?).ok()?;
+    let created = info.get("imageSpec")?.get("created")?.as_str()?;
+    DateTime::parse_from_rfc3339(created)
+        .ok()
+        .map(|d| d.timestamp())
+}
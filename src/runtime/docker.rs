@@ -1,14 +1,35 @@
-use std::{collections::HashMap, env, fmt::Display, fs, io::Write, path::PathBuf};
+use std::{
+    collections::HashMap,
+    env,
+    fmt::Display,
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
+use base64::{engine::general_purpose::STANDARD, Engine};
 use bollard::{
+    auth::DockerCredentials,
     container::{
-        InspectContainerOptions, ListContainersOptions, LogOutput, LogsOptions, MemoryStatsStats,
-        RemoveContainerOptions, Stats, StatsOptions,
+        Config, DownloadFromContainerOptions, InspectContainerOptions, ListContainersOptions,
+        LogOutput, LogsOptions, MemoryStatsStats, RemoveContainerOptions, Stats, StatsOptions,
+        StopContainerOptions, WaitContainerOptions,
     },
+    errors::Error,
     exec::{CreateExecOptions, ResizeExecOptions, StartExecResults},
-    image::{ListImagesOptions, RemoveImageOptions},
+    image::{
+        CommitContainerOptions, CreateImageOptions, ImportImageOptions, ListImagesOptions,
+        RemoveImageOptions,
+    },
     network::{InspectNetworkOptions, ListNetworksOptions},
-    service::{HealthStatusEnum, Network, Volume},
+    service::{
+        BuildInfo, ChangeType, ContainerTopResponse, CreateImageInfo, EventMessage,
+        HealthStatusEnum, Network, PortMap, Volume,
+    },
+    system::EventsOptions,
     volume::{ListVolumesOptions, RemoveVolumeOptions},
     Docker,
 };
@@ -25,18 +46,32 @@ use tokio::{
     io::{stdin, AsyncReadExt, AsyncWriteExt},
     select, spawn,
 };
-use tokio_util::sync::CancellationToken;
+use tokio_util::{
+    io::{StreamReader, SyncIoBridge},
+    sync::CancellationToken,
+};
 
-use crate::utils::get_or_not_found;
+use crate::config;
+use crate::utils::{get_or_not_found, short_id};
 
 use super::{
-    Compose, ContainerDetails, ContainerHealth, ContainerStatus, ContainerSummary, Filter,
-    ImageSummary, NetworkSummary, VolumeSummary,
+    container_name, Compose, ContainerDetails, ContainerHealth, ContainerStatus, ContainerSummary,
+    Event, ExecOptions, ExecResult, FileChange, FileChangeKind, Filter, HostDetails, ImageSummary,
+    MountDetails, NetworkSummary, Process, RegistryCredentials, Unauthorized, VolumeSummary,
 };
 
 const DEFAULT_TIMEOUT: u64 = 120;
+
 const DEFAULT_DOCKER_SOCKET_PATH: &str = "/var/run/docker.sock";
 
+#[cfg(target_os = "linux")]
+const ROOTLESS_DOCKER_SOCKET_PATH: &str = "docker.sock";
+#[cfg(target_os = "linux")]
+const ROOTLESS_PODMAN_SOCKET_PATH: &str = "podman/podman.sock";
+
+#[cfg(windows)]
+const DEFAULT_DOCKER_NAMED_PIPE: &str = "//./pipe/docker_engine";
+
 #[cfg(target_os = "macos")]
 const DEFAULT_RANCHER_DESKTOP_SOCKET_PATH: &str = ".rd/docker.sock";
 #[cfg(target_os = "macos")]
@@ -44,6 +79,15 @@ const DEFAULT_PODMAN_DESKTOP_SOCKET_PATH: &str =
     ".local/share/containers/podman/machine/podman.sock";
 #[cfg(target_os = "macos")]
 const DEFAULT_ORBSTACK_DESKTOP_SOCKET_PATH: &str = ".orbstack/run/docker.sock";
+#[cfg(target_os = "macos")]
+const DEFAULT_COLIMA_SOCKET_PATH: &str = ".colima/default/docker.sock";
+#[cfg(target_os = "macos")]
+const LEGACY_COLIMA_SOCKET_PATH: &str = ".colima/docker.sock";
+
+const SSH_DEFAULT_REMOTE_SOCKET: &str = "/var/run/docker.sock";
+const SSH_TUNNEL_TIMEOUT: Duration = Duration::from_secs(30);
+// An exec failing to start its command returns within this delay
+const EXEC_START_TIMEOUT: Duration = Duration::from_secs(1);
 
 const AVAILABLE_CONTAINER_FILTERS: [&str; 14] = [
     "ancestor", "before", "expose", "exited", "health", "id", "is-task", "label", "name",
@@ -61,7 +105,10 @@ const DOCKER_COMPOSE_ENV: &str = "com.docker.compose.project.environment_file";
 pub enum ConnectionConfig {
     Ssl(String, String),
     Http(String),
+    Ssh(String),
     Socket(Option<String>),
+    #[cfg(windows)]
+    NamedPipe(String),
 }
 
 #[allow(dead_code)]
@@ -81,6 +128,20 @@ impl ConnectionConfig {
     pub fn ssl(address: String, certs_path: String) -> Self {
         ConnectionConfig::Ssl(address, certs_path)
     }
+
+    pub fn is_remote(&self) -> bool {
+        match self {
+            ConnectionConfig::Ssl(_, _) | ConnectionConfig::Ssh(_) => true,
+            ConnectionConfig::Http(host) => !host.starts_with("unix://"),
+            ConnectionConfig::Socket(_) => false,
+            #[cfg(windows)]
+            ConnectionConfig::NamedPipe(_) => false,
+        }
+    }
+
+    pub fn is_interactive(&self) -> bool {
+        matches!(self, ConnectionConfig::Ssh(_))
+    }
 }
 
 impl Display for ConnectionConfig {
@@ -88,12 +149,15 @@ impl Display for ConnectionConfig {
         match self {
             ConnectionConfig::Ssl(host, _certs_path) => f.write_str(host),
             ConnectionConfig::Http(host) => f.write_str(host),
+            ConnectionConfig::Ssh(url) => f.write_str(url),
             ConnectionConfig::Socket(Some(socket_path)) => {
                 f.write_fmt(format_args!("unix://{}", socket_path))
             }
             ConnectionConfig::Socket(None) => {
                 f.write_fmt(format_args!("unix://{}", DEFAULT_DOCKER_SOCKET_PATH))
             }
+            #[cfg(windows)]
+            ConnectionConfig::NamedPipe(path) => f.write_fmt(format_args!("npipe://{}", path)),
         }
     }
 }
@@ -103,8 +167,8 @@ fn test_other_default_socket(relative_path: &str) -> Result<ConnectionConfig> {
     use eyre::eyre;
     use std::path::Path;
 
-    let home_dir = env!("HOME");
-    let socket_path = Path::new(home_dir).join(relative_path);
+    let home_dir = env::var("HOME")?;
+    let socket_path = Path::new(&home_dir).join(relative_path);
     let socket_path = socket_path
         .into_os_string()
         .into_string()
@@ -117,6 +181,10 @@ pub fn detect_connection_config() -> Option<ConnectionConfig> {
     let docker_host = env::var("DOCKER_HOST");
     let docker_cert = env::var("DOCKER_CERT_PATH");
     match (docker_host, docker_cert) {
+        (Ok(host), _) if host.starts_with("ssh://") => {
+            log::debug!("Connect with ssh");
+            Some(ConnectionConfig::Ssh(host))
+        }
         (Ok(host), Ok(certs)) => {
             log::debug!("Connect with ssl");
             Some(ConnectionConfig::Ssl(host, certs))
@@ -132,16 +200,70 @@ pub fn detect_connection_config() -> Option<ConnectionConfig> {
                 .or_else(|_| test_other_default_socket(DEFAULT_RANCHER_DESKTOP_SOCKET_PATH))
                 .or_else(|_| test_other_default_socket(DEFAULT_PODMAN_DESKTOP_SOCKET_PATH))
                 .or_else(|_| test_other_default_socket(DEFAULT_ORBSTACK_DESKTOP_SOCKET_PATH))
+                .or_else(|_| test_other_default_socket(DEFAULT_COLIMA_SOCKET_PATH))
+                .or_else(|_| test_other_default_socket(LEGACY_COLIMA_SOCKET_PATH))
                 .ok()
         }
     }
 }
 
+// Sockets of rootless docker and podman live in the user runtime directory
+#[cfg(target_os = "linux")]
+fn test_runtime_dir_socket(relative_path: &str) -> Result<ConnectionConfig> {
+    let runtime_dir = env::var("XDG_RUNTIME_DIR")?;
+    let socket_path = Path::new(&runtime_dir).join(relative_path);
+    let socket_path = socket_path
+        .into_os_string()
+        .into_string()
+        .map_err(|_| eyre!("Unable to convert path to string"))?;
+    fs::metadata(&socket_path).map(|_| Ok(ConnectionConfig::Socket(Some(socket_path))))?
+}
+
+// Sockets tried by `detect_connection_config`, listed when none of them exists
+#[cfg(target_os = "macos")]
+pub fn probed_sockets() -> Vec<String> {
+    let home_dir = env::var("HOME").unwrap_or("$HOME".to_string());
+    let mut sockets = vec![DEFAULT_DOCKER_SOCKET_PATH.to_string()];
+    sockets.extend(
+        [
+            DEFAULT_RANCHER_DESKTOP_SOCKET_PATH,
+            DEFAULT_PODMAN_DESKTOP_SOCKET_PATH,
+            DEFAULT_ORBSTACK_DESKTOP_SOCKET_PATH,
+            DEFAULT_COLIMA_SOCKET_PATH,
+            LEGACY_COLIMA_SOCKET_PATH,
+        ]
+        .iter()
+        .map(|p| Path::new(&home_dir).join(p).display().to_string()),
+    );
+    sockets
+}
+
+#[cfg(target_os = "linux")]
+pub fn probed_sockets() -> Vec<String> {
+    let runtime_dir = env::var("XDG_RUNTIME_DIR").unwrap_or("$XDG_RUNTIME_DIR".to_string());
+    let mut sockets = vec![DEFAULT_DOCKER_SOCKET_PATH.to_string()];
+    sockets.extend(
+        [ROOTLESS_DOCKER_SOCKET_PATH, ROOTLESS_PODMAN_SOCKET_PATH]
+            .iter()
+            .map(|p| Path::new(&runtime_dir).join(p).display().to_string()),
+    );
+    sockets
+}
+
+#[cfg(windows)]
+pub fn probed_sockets() -> Vec<String> {
+    vec![format!("npipe://{}", DEFAULT_DOCKER_NAMED_PIPE)]
+}
+
 #[cfg(target_os = "linux")]
 pub fn detect_connection_config() -> Option<ConnectionConfig> {
     let docker_host = env::var("DOCKER_HOST");
     let docker_cert = env::var("DOCKER_CERT_PATH");
     match (docker_host, docker_cert) {
+        (Ok(host), _) if host.starts_with("ssh://") => {
+            log::debug!("Connect with ssh");
+            Some(ConnectionConfig::Ssh(host))
+        }
         (Ok(host), Ok(certs)) => {
             log::debug!("Connect with ssl");
             Some(ConnectionConfig::Ssl(host, certs))
@@ -152,16 +274,127 @@ pub fn detect_connection_config() -> Option<ConnectionConfig> {
         }
         _ => {
             log::debug!("Connect with socket");
-            match fs::metadata(DEFAULT_DOCKER_SOCKET_PATH) {
-                Ok(_) => Some(ConnectionConfig::default_socket()),
-                Err(_) => None,
-            }
+            fs::metadata(DEFAULT_DOCKER_SOCKET_PATH)
+                .map(|_| ConnectionConfig::default_socket())
+                .or_else(|_| test_runtime_dir_socket(ROOTLESS_DOCKER_SOCKET_PATH))
+                .or_else(|_| test_runtime_dir_socket(ROOTLESS_PODMAN_SOCKET_PATH))
+                .ok()
+        }
+    }
+}
+
+#[cfg(windows)]
+pub fn detect_connection_config() -> Option<ConnectionConfig> {
+    let docker_host = env::var("DOCKER_HOST");
+    let docker_cert = env::var("DOCKER_CERT_PATH");
+    match (docker_host, docker_cert) {
+        (Ok(host), _) if host.starts_with("npipe://") => {
+            log::debug!("Connect with named pipe");
+            let path = host.trim_start_matches("npipe://").to_string();
+            Some(ConnectionConfig::NamedPipe(path))
+        }
+        (Ok(host), _) if host.starts_with("ssh://") => {
+            log::debug!("Connect with ssh");
+            Some(ConnectionConfig::Ssh(host))
+        }
+        (Ok(host), Ok(certs)) => {
+            log::debug!("Connect with ssl");
+            Some(ConnectionConfig::Ssl(host, certs))
+        }
+        (Ok(host), Err(_)) => {
+            log::debug!("Connect with http");
+            Some(ConnectionConfig::Http(host))
+        }
+        _ => {
+            log::debug!("Connect with named pipe");
+            Some(ConnectionConfig::NamedPipe(
+                DEFAULT_DOCKER_NAMED_PIPE.to_string(),
+            ))
         }
     }
 }
 
+#[derive(Clone)]
 pub struct Client {
     client: Docker,
+    _tunnel: Option<Arc<SshTunnel>>,
+    // Architecture and OS by image id, an image content never changes so they are inspected once
+    platforms: Arc<Mutex<HashMap<String, (String, String)>>>,
+}
+
+// Forwards a local unix socket to the docker socket of a remote host with `ssh`.
+// The ssh process is stopped when the last clone of the client is dropped.
+struct SshTunnel {
+    process: Child,
+    socket: PathBuf,
+}
+
+impl SshTunnel {
+    // Accepts `ssh://[user@]host[:port][/remote/socket/path]`
+    fn open(url: &str) -> Result<Self> {
+        let address = url
+            .strip_prefix("ssh://")
+            .ok_or_else(|| eyre!("Invalid ssh url: {}", url))?;
+        let (destination, remote_socket) = match address.split_once('/') {
+            Some((destination, path)) => (destination, format!("/{}", path)),
+            None => (address, SSH_DEFAULT_REMOTE_SOCKET.to_string()),
+        };
+        let (destination, port) = match destination.rsplit_once(':') {
+            Some((destination, port)) => (destination, Some(port)),
+            None => (destination, None),
+        };
+
+        let socket = env::temp_dir().join(format!("doggy-ssh-{}.sock", std::process::id()));
+        let _ = fs::remove_file(&socket);
+
+        let mut command = Command::new("ssh");
+        command
+            .args(["-N", "-o", "ExitOnForwardFailure=yes", "-L"])
+            .arg(format!("{}:{}", socket.display(), remote_socket));
+        if let Some(port) = port {
+            command.args(["-p", port]);
+        }
+        let process = command
+            .arg(destination)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| eyre!("Unable to launch ssh: {}", e))?;
+
+        let mut tunnel = SshTunnel { process, socket };
+        let start = Instant::now();
+        while !tunnel.socket.exists() {
+            if let Some(status) = tunnel.process.try_wait()? {
+                let mut stderr = String::new();
+                if let Some(mut err) = tunnel.process.stderr.take() {
+                    let _ = err.read_to_string(&mut stderr);
+                }
+                Err(eyre!(
+                    "Unable to open a ssh tunnel to {} ({}):\n{}",
+                    destination,
+                    status,
+                    stderr.trim()
+                ))?;
+            }
+            if start.elapsed() > SSH_TUNNEL_TIMEOUT {
+                Err(eyre!(
+                    "Timeout while opening a ssh tunnel to {}",
+                    destination
+                ))?;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        Ok(tunnel)
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+        let _ = fs::remove_file(&self.socket);
+    }
 }
 
 impl Client {
@@ -250,25 +483,61 @@ impl Client {
         Ok(())
     }
 
-    pub(crate) async fn list_images(&self, filter: &Option<String>) -> Result<Vec<ImageSummary>> {
-        let options: ListImagesOptions<String> = Default::default();
+    pub(crate) async fn list_images(&self, filter: &Filter) -> Result<Vec<ImageSummary>> {
+        // The platform is not a runtime filter, it is applied on the inspected images
+        let arch = filter.get("arch").cloned();
+        let options: ListImagesOptions<String> = ListImagesOptions {
+            filters: filter.clone().without("arch").into(),
+            ..Default::default()
+        };
         let images = self.client.list_images(Some(options)).await?;
-        let images = images
-            .iter()
-            .map(|i: &bollard::service::ImageSummary| ImageSummary {
-                id: i.id.split(':').last().unwrap_or("NOT_FOUND").to_string(),
-                name: get_or_not_found!(i.repo_tags.first()),
-                size: i.size,
-                created: i.created,
-            })
-            .filter(|i| match filter {
-                Some(f) => i.name.contains(f),
-                None => true,
-            })
-            .collect();
+        let images = futures::future::join_all(images.iter().map(
+            |i: &bollard::service::ImageSummary| async {
+                let (architecture, os) = self.image_platform(&i.id).await;
+                ImageSummary {
+                    id: i
+                        .id
+                        .split(':')
+                        .next_back()
+                        .unwrap_or("NOT_FOUND")
+                        .to_string(),
+                    name: get_or_not_found!(i.repo_tags.first()),
+                    size: i.size,
+                    created: Some(i.created),
+                    architecture,
+                    os,
+                }
+            },
+        ))
+        .await
+        .into_iter()
+        .filter(|i| match &arch {
+            Some(a) => &i.architecture == a,
+            None => true,
+        })
+        .collect();
         Ok(images)
     }
 
+    async fn image_platform(&self, id: &str) -> (String, String) {
+        if let Some(platform) = self.platforms.lock().ok().and_then(|p| p.get(id).cloned()) {
+            return platform;
+        }
+        match self.client.inspect_image(id).await {
+            Ok(inspect) => {
+                let platform = (
+                    inspect.architecture.unwrap_or_default(),
+                    inspect.os.unwrap_or_default(),
+                );
+                if let Ok(mut platforms) = self.platforms.lock() {
+                    platforms.insert(id.to_string(), platform.clone());
+                }
+                platform
+            }
+            Err(_) => Default::default(),
+        }
+    }
+
     pub(crate) async fn get_image(&self, id: &str) -> Result<String> {
         let image = self.client.inspect_image(id).await?;
         Ok(serde_json::to_string_pretty(&image)?)
@@ -283,6 +552,93 @@ impl Client {
         Ok(())
     }
 
+    pub(crate) async fn pull_image(
+        &self,
+        reference: &str,
+        credentials: Option<RegistryCredentials>,
+    ) -> Result<impl Stream<Item = Result<String>>> {
+        let registry = registry_of(reference);
+        let credentials = match credentials {
+            Some(c) => Some(DockerCredentials {
+                username: Some(c.username),
+                password: Some(c.password),
+                serveraddress: Some(registry),
+                ..Default::default()
+            }),
+            // Credential helpers may be slow or wait for the user (e.g. a keychain prompt)
+            None => {
+                tokio::task::spawn_blocking(move || docker_config_credentials(&registry)).await?
+            }
+        };
+        // Without a tag, the daemon would pull every tag of the repository
+        let (from_image, tag) = match reference.rsplit_once(':') {
+            Some((image, tag)) if !reference.contains('@') && !tag.contains('/') => {
+                (image.to_string(), tag.to_string())
+            }
+            _ if reference.contains('@') => (reference.to_string(), String::new()),
+            _ => (reference.to_string(), "latest".to_string()),
+        };
+        let options = CreateImageOptions {
+            from_image,
+            tag,
+            ..Default::default()
+        };
+        let stream = self.client.create_image(Some(options), None, credentials);
+        Ok(stream.map(|item| match item {
+            Ok(CreateImageInfo { error: Some(e), .. }) => Err(pull_error(e)),
+            Ok(info) => Ok(info.status.unwrap_or_default()),
+            Err(Error::DockerResponseServerError { message, .. }) => Err(pull_error(message)),
+            Err(e) => Err(e.into()),
+        }))
+    }
+
+    // Streams the archive to disk so large images are never held in memory
+    pub(crate) async fn save_image(&self, id: &str, dest: PathBuf) -> Result<u64> {
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut file = tokio::fs::File::create(&dest).await?;
+        let mut stream = self.client.export_image(id);
+        let mut written = 0;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            file.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        file.flush().await?;
+        Ok(written)
+    }
+
+    pub(crate) async fn load_image(
+        &self,
+        src: PathBuf,
+    ) -> Result<impl Stream<Item = Result<String>>> {
+        let file = tokio::fs::File::open(&src).await?;
+        let body = hyper::Body::wrap_stream(tokio_util::io::ReaderStream::new(file));
+        let stream = self
+            .client
+            .import_image(ImportImageOptions { quiet: false }, body, None);
+        Ok(stream.map(|item| match item {
+            Ok(BuildInfo { error: Some(e), .. }) => Err(eyre!(e)),
+            Ok(info) => Ok(info.stream.or(info.status).unwrap_or_default()),
+            Err(e) => Err(e.into()),
+        }))
+    }
+
+    pub(crate) async fn commit_container(&self, cid: &str, repo: &str, tag: &str) -> Result<()> {
+        let options = CommitContainerOptions {
+            container: cid,
+            repo,
+            tag,
+            pause: true,
+            ..Default::default()
+        };
+        self.client
+            .commit_container(options, Config::<String>::default())
+            .await?;
+        Ok(())
+    }
+
     pub(crate) async fn delete_container(&self, cid: &str) -> Result<()> {
         let options = RemoveContainerOptions {
             force: true,
@@ -292,6 +648,28 @@ impl Client {
         Ok(())
     }
 
+    pub(crate) async fn stop_container(&self, cid: &str) -> Result<()> {
+        self.client
+            .stop_container(cid, None::<StopContainerOptions>)
+            .await?;
+        Ok(())
+    }
+
+    // Resolves with the exit code once the container is no longer running
+    pub(crate) async fn wait_container(&self, cid: &str) -> Result<i64> {
+        let options = WaitContainerOptions {
+            condition: "not-running",
+        };
+        let mut stream = self.client.wait_container(cid, Some(options));
+        match stream.next().await {
+            Some(Ok(response)) => Ok(response.status_code),
+            // A non zero exit code is reported as an error by bollard
+            Some(Err(Error::DockerContainerWaitError { code, .. })) => Ok(code),
+            Some(Err(e)) => Err(e.into()),
+            None => Err(eyre!("No response while waiting for container {}", cid)),
+        }
+    }
+
     pub(crate) async fn list_containers(
         &self,
         all: bool,
@@ -307,12 +685,19 @@ impl Client {
             .iter()
             .map(|c| ContainerSummary {
                 id: get_or_not_found!(c.id),
-                name: get_or_not_found!(c.names, |c| c.first().and_then(|s| s.split('/').last())),
+                name: container_name(
+                    c.names
+                        .as_ref()
+                        .and_then(|n| n.first())
+                        .and_then(|s| s.split('/').next_back()),
+                    c.id.as_deref().unwrap_or_default(),
+                ),
                 image: get_or_not_found!(c.image, |i| i.split('@').next()),
                 image_id: get_or_not_found!(c.image_id),
                 labels: c.labels.clone().unwrap_or_default(),
                 status: c.state.clone().unwrap_or("unknown".into()).into(),
                 age: c.created.unwrap_or_default(),
+                started: None,
             })
             .collect();
         Ok(containers)
@@ -326,6 +711,24 @@ impl Client {
         Ok(serde_json::to_string_pretty(&container_details)?)
     }
 
+    pub(crate) async fn get_container_restart_count(&self, cid: &str) -> Result<i64> {
+        let container_details = self
+            .client
+            .inspect_container(cid, Some(InspectContainerOptions { size: false }))
+            .await?;
+        Ok(container_details.restart_count.unwrap_or_default())
+    }
+
+    pub(crate) async fn get_container_started_at(&self, cid: &str) -> Result<Option<i64>> {
+        let container_details = self
+            .client
+            .inspect_container(cid, Some(InspectContainerOptions { size: false }))
+            .await?;
+        Ok(parse_started(
+            container_details.state.and_then(|s| s.started_at),
+        ))
+    }
+
     pub(crate) async fn get_container_details(&self, cid: String) -> Result<ContainerDetails> {
         let container_details = self
             .client
@@ -334,13 +737,26 @@ impl Client {
         let config = container_details
             .config
             .ok_or(eyre!("No container configuration"))?;
+        let started = parse_started(
+            container_details
+                .state
+                .as_ref()
+                .and_then(|s| s.started_at.clone()),
+        );
         let status = parse_state(container_details.state);
+        let published_ports = container_details
+            .network_settings
+            .as_ref()
+            .and_then(|n| n.ports.clone())
+            .unwrap_or_default();
         let container_top = match status {
             super::ContainerStatus::Running(_) => self
                 .client
                 .top_processes(
                     &cid,
-                    Some(bollard::container::TopOptions { ps_args: "aux" }),
+                    Some(bollard::container::TopOptions {
+                        ps_args: config::get().top.ps_args.as_str(),
+                    }),
                 )
                 .await
                 .ok(),
@@ -348,8 +764,15 @@ impl Client {
         };
         Ok(ContainerDetails {
             id: cid.to_string(),
-            name: parse_name(container_details.name),
+            name: container_name(
+                container_details
+                    .name
+                    .as_deref()
+                    .and_then(|s| s.split('/').next_back()),
+                &cid,
+            ),
             age: parse_created(container_details.created),
+            started,
             image: config.image,
             image_id: container_details.image,
             labels: config.labels.unwrap_or_default(),
@@ -357,11 +780,77 @@ impl Client {
             command: config.cmd,
             status,
             env: parse_env(config.env),
-            ports: parse_ports(config.exposed_ports),
+            ports: parse_ports(config.exposed_ports, published_ports),
             network: parse_networks(container_details.network_settings),
-            volumes: parse_mounts(container_details.mounts),
-            processes: parse_processes(container_top.and_then(|t| t.processes)),
+            mounts: parse_mounts(container_details.mounts),
+            processes: container_top.map(parse_processes).unwrap_or_default(),
+            host: parse_host_config(container_details.host_config),
+        })
+    }
+
+    pub(crate) async fn get_container_processes(&self, cid: &str) -> Result<Vec<Process>> {
+        let top = self
+            .client
+            .top_processes(
+                cid,
+                Some(bollard::container::TopOptions {
+                    ps_args: config::get().top.ps_args.as_str(),
+                }),
+            )
+            .await?;
+        Ok(parse_processes(top))
+    }
+
+    pub(crate) async fn container_changes(&self, cid: &str) -> Result<Vec<FileChange>> {
+        let changes = self.client.container_changes(cid).await?;
+        let mut changes: Vec<FileChange> = changes
+            .unwrap_or_default()
+            .into_iter()
+            .map(|c| FileChange {
+                path: c.path,
+                kind: match c.kind {
+                    ChangeType::_0 => FileChangeKind::Modified,
+                    ChangeType::_1 => FileChangeKind::Added,
+                    ChangeType::_2 => FileChangeKind::Deleted,
+                },
+            })
+            .collect();
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(changes)
+    }
+
+    pub(crate) async fn download_from_container(
+        &self,
+        cid: &str,
+        path: &str,
+        dest: PathBuf,
+    ) -> Result<PathBuf> {
+        let options = DownloadFromContainerOptions {
+            path: path.to_string(),
+        };
+        let mut stream = Box::pin(self.client.download_from_container(cid, Some(options)));
+        // A missing path is reported by the first chunk, nothing is written to disk then
+        let first = match stream.next().await {
+            Some(Err(Error::DockerResponseServerError {
+                status_code: 404, ..
+            })) => Err(eyre!("Path \"{}\" not found in container", path))?,
+            first => first.transpose()?,
+        };
+        let stream = futures::stream::iter(first.map(Ok))
+            .chain(stream)
+            .map(|chunk| chunk.map_err(std::io::Error::other));
+        // The archive is unpacked as it is received
+        let archive = SyncIoBridge::new(StreamReader::new(stream));
+        let target = dest.clone();
+        tokio::task::spawn_blocking(move || {
+            fs::create_dir_all(&target)?;
+            tar::Archive::new(archive).unpack(&target)
         })
+        .await??;
+        match Path::new(path).file_name() {
+            Some(name) => Ok(dest.join(name)),
+            None => Ok(dest),
+        }
     }
 
     pub(crate) fn get_container_logs(
@@ -388,15 +877,30 @@ impl Client {
         }))
     }
 
+    pub(crate) fn get_events(&self) -> Result<impl Stream<Item = Result<Event>>> {
+        let types = ["container", "image", "network", "volume"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let options = EventsOptions::<String> {
+            filters: HashMap::from([("type".to_string(), types)]),
+            ..Default::default()
+        };
+        let stream = self.client.events(Some(options));
+        Ok(stream.map(|item| match item {
+            Err(e) => Err(color_eyre::Report::from(e)),
+            Ok(event) => Ok(parse_event(event)),
+        }))
+    }
+
     pub(crate) async fn list_compose_projects(&self) -> Result<Vec<Compose>> {
-        let c: Vec<ContainerDetails> = futures::future::try_join_all(
-            self.list_containers(true, &Filter::default().compose())
-                .await?
-                .into_iter()
-                .filter(|c| c.labels.contains_key(DOCKER_COMPOSE_PROJECT))
-                .map(|c| self.get_container_details(c.id.to_string())),
-        )
-        .await?;
+        // Only the summaries are listed, the details of a service are inspected when displayed
+        let c: Vec<ContainerSummary> = self
+            .list_containers(true, &Filter::default().compose())
+            .await?
+            .into_iter()
+            .filter(|c| c.labels.contains_key(DOCKER_COMPOSE_PROJECT))
+            .collect();
         let v: Vec<VolumeSummary> = self
             .list_volumes(&Filter::default().compose())
             .await?
@@ -462,10 +966,16 @@ impl Client {
             projects
         });
 
-        Ok(projects.into_values().collect())
+        let mut projects: Vec<Compose> = projects.into_values().collect();
+        projects.sort_by(|a, b| a.project.cmp(&b.project));
+        Ok(projects)
     }
 
-    pub(crate) async fn container_exec(&self, cid: &str, cmd: &str) -> Result<()> {
+    pub(crate) async fn container_exec(
+        &self,
+        cid: &str,
+        options: &ExecOptions,
+    ) -> Result<ExecResult> {
         let cancellation_token = CancellationToken::new();
         let _cancellation_token = cancellation_token.clone();
         let tty_size = crossterm::terminal::size()?;
@@ -480,13 +990,17 @@ impl Client {
                     attach_stderr: Some(true),
                     attach_stdin: Some(true),
                     tty: Some(true),
-                    cmd: Some(vec![cmd]),
+                    cmd: Some(vec![options.cmd.as_str()]),
+                    user: options.user.as_deref(),
+                    working_dir: options.working_dir.as_deref(),
+                    env: Some(options.env.iter().map(String::as_str).collect()),
                     ..Default::default()
                 },
             )
             .await?
             .id;
 
+        let started_at = Instant::now();
         if let StartExecResults::Attached {
             mut output,
             mut input,
@@ -530,7 +1044,14 @@ impl Client {
             cancellation_token.cancel();
             handle.await?;
         }
-        Ok(())
+        let inspect = self.client.inspect_exec(&exec).await?;
+        // The pid is not set when the process could not be started, a failed start also
+        // returns immediately, which covers the runtimes not keeping the pid once exited
+        Ok(ExecResult {
+            exit_code: inspect.exit_code,
+            started: inspect.pid.is_some_and(|pid| pid > 0)
+                || started_at.elapsed() >= EXEC_START_TIMEOUT,
+        })
     }
 
     pub(crate) async fn info(&self) -> Result<(String, String)> {
@@ -573,31 +1094,24 @@ fn extract_compose_info(
     )
 }
 
-fn parse_processes(processes: Option<Vec<Vec<String>>>) -> Vec<(String, String, String)> {
-    processes
-        .map(|ps| {
-            ps.into_iter()
-                .map(|p| {
-                    (
-                        p.first().cloned().unwrap_or_default(),
-                        p.get(1).cloned().unwrap_or_default(),
-                        p.get(10).cloned().unwrap_or_default(),
-                    )
-                })
-                .collect::<Vec<(String, String, String)>>()
-        })
+fn parse_processes(top: ContainerTopResponse) -> Vec<Process> {
+    let titles = top.titles.unwrap_or_default();
+    top.processes
         .unwrap_or_default()
+        .iter()
+        .map(|p| Process::parse(&titles, p))
+        .collect()
 }
 
-fn parse_mounts(mounts: Option<Vec<bollard::service::MountPoint>>) -> Vec<(String, String)> {
-    let mut mounts: Vec<(String, String)> = mounts
+fn parse_mounts(mounts: Option<Vec<bollard::service::MountPoint>>) -> Vec<MountDetails> {
+    let mut mounts: Vec<MountDetails> = mounts
         .map(|m| {
             m.into_iter()
-                .map(|m| {
-                    (
-                        m.source.unwrap_or_default(),
-                        m.destination.unwrap_or_default(),
-                    )
+                .map(|m| MountDetails {
+                    source: m.source.unwrap_or_default(),
+                    destination: m.destination.unwrap_or_default(),
+                    kind: m.typ.map_or("unknown".to_string(), |t| t.to_string()),
+                    rw: m.rw.unwrap_or_default(),
                 })
                 .collect::<Vec<_>>()
         })
@@ -606,6 +1120,130 @@ fn parse_mounts(mounts: Option<Vec<bollard::service::MountPoint>>) -> Vec<(Strin
     mounts
 }
 
+fn pull_error(message: String) -> color_eyre::Report {
+    let lower = message.to_lowercase();
+    if ["unauthorized", "authentication required", "denied"]
+        .iter()
+        .any(|m| lower.contains(m))
+    {
+        Unauthorized(message).into()
+    } else {
+        eyre!(message)
+    }
+}
+
+const DOCKER_HUB_REGISTRY: &str = "https://index.docker.io/v1/";
+
+fn registry_of(reference: &str) -> String {
+    match reference.split_once('/') {
+        // The Docker Hub aliases all share the credentials stored for the legacy index
+        Some(("docker.io" | "index.docker.io" | "registry-1.docker.io", _)) => {
+            DOCKER_HUB_REGISTRY.to_string()
+        }
+        Some((host, _)) if host.contains('.') || host.contains(':') || host == "localhost" => {
+            host.to_string()
+        }
+        _ => DOCKER_HUB_REGISTRY.to_string(),
+    }
+}
+
+// Looks up credentials the same way the docker CLI does: credential helpers first,
+// then the base64 encoded `auths` entries of ~/.docker/config.json
+fn docker_config_credentials(registry: &str) -> Option<DockerCredentials> {
+    let config_dir = env::var("DOCKER_CONFIG")
+        .map(PathBuf::from)
+        .or_else(|_| env::var("HOME").map(|h| Path::new(&h).join(".docker")))
+        .ok()?;
+    let config: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(config_dir.join("config.json")).ok()?).ok()?;
+    let helper = config
+        .get("credHelpers")
+        .and_then(|h| h.get(registry))
+        .or_else(|| config.get("credsStore"))
+        .and_then(|h| h.as_str());
+    if let Some(helper) = helper {
+        if let Some(credentials) = credential_helper(helper, registry) {
+            return Some(credentials);
+        }
+    }
+    let auth = config.get("auths")?.get(registry)?;
+    if let Some(token) = auth.get("identitytoken").and_then(|t| t.as_str()) {
+        return Some(DockerCredentials {
+            identitytoken: Some(token.to_string()),
+            serveraddress: Some(registry.to_string()),
+            ..Default::default()
+        });
+    }
+    let decoded = STANDARD
+        .decode(auth.get("auth")?.as_str()?)
+        .ok()
+        .and_then(|d| String::from_utf8(d).ok())?;
+    let (username, password) = decoded.split_once(':')?;
+    Some(DockerCredentials {
+        username: Some(username.to_string()),
+        password: Some(password.to_string()),
+        serveraddress: Some(registry.to_string()),
+        ..Default::default()
+    })
+}
+
+fn credential_helper(helper: &str, registry: &str) -> Option<DockerCredentials> {
+    let mut child = std::process::Command::new(format!("docker-credential-{}", helper))
+        .arg("get")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .ok()?;
+    child.stdin.take()?.write_all(registry.as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let credentials: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    let username = credentials.get("Username")?.as_str()?;
+    let secret = credentials.get("Secret")?.as_str()?.to_string();
+    Some(if username == "<token>" {
+        DockerCredentials {
+            identitytoken: Some(secret),
+            serveraddress: Some(registry.to_string()),
+            ..Default::default()
+        }
+    } else {
+        DockerCredentials {
+            username: Some(username.to_string()),
+            password: Some(secret),
+            serveraddress: Some(registry.to_string()),
+            ..Default::default()
+        }
+    })
+}
+
+fn parse_host_config(host_config: Option<bollard::service::HostConfig>) -> HostDetails {
+    let host_config = host_config.unwrap_or_default();
+    HostDetails {
+        privileged: host_config.privileged.unwrap_or_default(),
+        cpus: host_config
+            .nano_cpus
+            .filter(|c| *c > 0)
+            .map(|c| c as f64 / 1_000_000_000.0),
+        memory: host_config.memory.filter(|m| *m > 0),
+        cap_add: host_config.cap_add.unwrap_or_default(),
+        cap_drop: host_config.cap_drop.unwrap_or_default(),
+        devices: host_config
+            .devices
+            .unwrap_or_default()
+            .into_iter()
+            .map(|d| {
+                (
+                    d.path_on_host.unwrap_or_default(),
+                    d.path_in_container.unwrap_or_default(),
+                )
+            })
+            .collect(),
+    }
+}
+
 fn parse_networks(
     network_settings: Option<bollard::service::NetworkSettings>,
 ) -> Vec<(String, Option<String>)> {
@@ -619,14 +1257,51 @@ fn parse_networks(
     nets
 }
 
-fn parse_ports(exposed_ports: Option<HashMap<String, HashMap<(), ()>>>) -> Vec<(String, String)> {
+// Exposed ports with the host address they are published on, or an empty string
+fn parse_ports(
+    exposed_ports: Option<HashMap<String, HashMap<(), ()>>>,
+    published_ports: PortMap,
+) -> Vec<(String, String)> {
     let mut ports: Vec<(String, String)> = exposed_ports
-        .map(|ports| ports.keys().cloned().map(|p| (p, String::new())).collect())
+        .map(|ports| {
+            ports
+                .keys()
+                .map(|p| {
+                    let binding = published_ports
+                        .get(p)
+                        .and_then(|b| b.as_ref())
+                        .and_then(|b| b.first())
+                        .map(|b| {
+                            format!(
+                                "{}:{}",
+                                b.host_ip.as_deref().unwrap_or_default(),
+                                b.host_port.as_deref().unwrap_or_default()
+                            )
+                        })
+                        .unwrap_or_default();
+                    (p.to_string(), binding)
+                })
+                .collect()
+        })
         .unwrap_or_default();
     ports.sort();
     ports
 }
 
+fn parse_event(event: EventMessage) -> Event {
+    let actor = event.actor.unwrap_or_default();
+    let id = actor.id.unwrap_or_default();
+    Event {
+        time: event.time.unwrap_or_default(),
+        kind: event.typ.map(|t| t.to_string()).unwrap_or_default(),
+        action: event.action.unwrap_or_default(),
+        actor: actor
+            .attributes
+            .and_then(|mut a| a.remove("name"))
+            .unwrap_or_else(|| short_id(&id).to_string()),
+    }
+}
+
 fn parse_state(state: Option<bollard::service::ContainerState>) -> super::ContainerStatus {
     if state.is_none() {
         return ContainerStatus::Unknown;
@@ -669,9 +1344,9 @@ fn parse_created(created: Option<String>) -> Option<i64> {
         .map(|d| d.timestamp())
 }
 
-fn parse_name(name: Option<String>) -> String {
-    name.and_then(|s| s.split('/').last().map(String::from))
-        .unwrap_or("<UNKNOWN>".to_string())
+// Containers never started have a zero date (0001-01-01T00:00:00Z)
+fn parse_started(started_at: Option<String>) -> Option<i64> {
+    parse_created(started_at).filter(|t| *t > 0)
 }
 
 fn parse_env(env: Option<Vec<String>>) -> Vec<(String, String)> {
@@ -693,6 +1368,7 @@ fn parse_env(env: Option<Vec<String>>) -> Vec<(String, String)> {
 }
 
 pub(crate) fn connect(config: &ConnectionConfig) -> Result<Client> {
+    let mut tunnel = None;
     let docker = match config {
         ConnectionConfig::Ssl(host, certs_path) => {
             let mut ca = PathBuf::from(certs_path);
@@ -719,12 +1395,30 @@ pub(crate) fn connect(config: &ConnectionConfig) -> Result<Client> {
         ConnectionConfig::Http(host) => {
             Docker::connect_with_http(host, DEFAULT_TIMEOUT, bollard::API_DEFAULT_VERSION)?
         }
+        ConnectionConfig::Ssh(url) => {
+            let ssh = SshTunnel::open(url)?;
+            let docker = Docker::connect_with_socket(
+                &ssh.socket.to_string_lossy(),
+                DEFAULT_TIMEOUT,
+                bollard::API_DEFAULT_VERSION,
+            )?;
+            tunnel = Some(Arc::new(ssh));
+            docker
+        }
         ConnectionConfig::Socket(None) => Docker::connect_with_socket_defaults()?,
+        #[cfg(windows)]
+        ConnectionConfig::NamedPipe(path) => {
+            Docker::connect_with_named_pipe(path, DEFAULT_TIMEOUT, bollard::API_DEFAULT_VERSION)?
+        }
         ConnectionConfig::Socket(Some(path)) => {
             Docker::connect_with_socket(path, DEFAULT_TIMEOUT, bollard::API_DEFAULT_VERSION)?
         }
     };
-    Ok(Client { client: docker })
+    Ok(Client {
+        client: docker,
+        _tunnel: tunnel,
+        platforms: Default::default(),
+    })
 }
 
 pub fn compute_cpu(stats: &Stats) -> Option<f64> {
@@ -769,3 +1463,28 @@ pub fn compute_mem(stats: &Stats) -> Option<u64> {
         Some(usage)
     }
 }
+
+pub fn compute_net(stats: &Stats) -> Option<(u64, u64)> {
+    let networks = stats.networks.as_ref()?;
+    Some(networks.values().fold((0, 0), |(rx, tx), n| {
+        (rx.saturating_add(n.rx_bytes), tx.saturating_add(n.tx_bytes))
+    }))
+}
+
+pub fn compute_blkio(stats: &Stats) -> Option<(u64, u64)> {
+    let entries = stats.blkio_stats.io_service_bytes_recursive.as_ref()?;
+    Some(entries.iter().fold(
+        (0, 0),
+        |(read, write), e| match e.op.to_lowercase().as_str() {
+            "read" => (read.saturating_add(e.value), write),
+            "write" => (read, write.saturating_add(e.value)),
+            _ => (read, write),
+        },
+    ))
+}
+
+pub fn compute_read_time(stats: &Stats) -> Option<i64> {
+    DateTime::parse_from_rfc3339(&stats.read)
+        .ok()
+        .map(|d| d.timestamp_millis())
+}
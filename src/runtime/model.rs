@@ -1,10 +1,13 @@
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     fmt::Display,
 };
 
 use bollard::service::ContainerStateStatusEnum;
+use chrono::{DateTime, Local};
 use humansize::{FormatSizeI, BINARY};
+use regex::Regex;
+use serde::{Serialize, Serializer};
 
 use ratatui::{
     style::{Style, Stylize},
@@ -12,14 +15,16 @@ use ratatui::{
     widgets::{Cell, Row},
 };
 
-use crate::utils::Age;
+use crate::config;
+use crate::utils::{short_id, Age};
 
 use super::ConnectionConfig;
 
 #[allow(dead_code)]
 #[derive(Clone, Debug, Default)]
 pub struct Filter {
-    filter: HashMap<String, String>,
+    // Ordered so the filters are always displayed the same way
+    filter: BTreeMap<String, String>,
 }
 
 #[allow(dead_code)]
@@ -29,6 +34,15 @@ impl Filter {
         self
     }
 
+    pub fn get(&self, key: &str) -> Option<&String> {
+        self.filter.get(key)
+    }
+
+    pub fn without(mut self, key: &str) -> Self {
+        self.filter.remove(key);
+        self
+    }
+
     pub fn name(self, name: String) -> Self {
         self.filter("name".to_string(), name)
     }
@@ -37,6 +51,10 @@ impl Filter {
         self.filter("ancestor".to_string(), image)
     }
 
+    pub fn dangling(self) -> Self {
+        self.filter("dangling".to_string(), "true".to_string())
+    }
+
     pub fn compose(self) -> Self {
         self.filter(
             "label".to_string(),
@@ -112,6 +130,119 @@ impl From<String> for Filter {
     }
 }
 
+#[derive(Clone, Debug)]
+pub enum NameFilter {
+    Fuzzy(String),
+    Regex(Regex),
+}
+
+impl NameFilter {
+    pub fn matches(&self, value: &str) -> bool {
+        match self {
+            NameFilter::Fuzzy(pattern) => {
+                let value = value.to_lowercase();
+                let mut chars = value.chars();
+                pattern
+                    .to_lowercase()
+                    .chars()
+                    .all(|p| chars.any(|c| c == p))
+            }
+            NameFilter::Regex(regex) => regex.is_match(value),
+        }
+    }
+
+    pub fn format(&self) -> String {
+        match self {
+            NameFilter::Fuzzy(pattern) => format!(" - Search: {}", pattern),
+            NameFilter::Regex(regex) => format!(" - Regex: {}", regex),
+        }
+    }
+}
+
+impl Display for NameFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NameFilter::Fuzzy(pattern) => f.write_str(pattern),
+            NameFilter::Regex(regex) => write!(f, "~{}", regex),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RegistryCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+// Returned when a registry refuses an image pull without valid credentials
+#[derive(Debug)]
+pub struct Unauthorized(pub String);
+
+impl Display for Unauthorized {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Unauthorized {}
+
+#[derive(Clone, Debug, Default)]
+pub struct ExecOptions {
+    pub cmd: String,
+    pub user: Option<String>,
+    pub working_dir: Option<String>,
+    pub env: Vec<String>,
+}
+
+impl ExecOptions {
+    // Later entries override earlier ones with the same key
+    pub fn with_env(mut self, env: Vec<String>) -> Self {
+        let mut merged: Vec<String> = Vec::new();
+        for entry in env.into_iter().chain(self.env) {
+            let key = entry.split('=').next().unwrap_or_default().to_string();
+            merged.retain(|e| e.split('=').next() != Some(key.as_str()));
+            merged.push(entry);
+        }
+        self.env = merged;
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ExecResult {
+    pub exit_code: Option<i64>,
+    // False when the command could not be started, e.g. when it is not found in the container
+    pub started: bool,
+}
+
+impl From<&str> for ExecOptions {
+    // Accepts an optional `[user]@[workdir]` prefix and `KEY=VALUE` entries before the command,
+    // e.g. `root@/tmp TERM=xterm /bin/sh`
+    fn from(value: &str) -> Self {
+        let mut words = value.split_whitespace().peekable();
+        let (user, working_dir) = match words
+            .next_if(|w| w.contains('@') && !w.contains('='))
+            .and_then(|w| w.split_once('@'))
+        {
+            Some((user, working_dir)) => (
+                Some(user.to_string()).filter(|u| !u.is_empty()),
+                Some(working_dir.to_string()).filter(|w| !w.is_empty()),
+            ),
+            None => (None, None),
+        };
+        let mut env = Vec::new();
+        while let Some(entry) = words.next_if(|w| w.contains('=')) {
+            env.push(entry.to_string());
+        }
+        ExecOptions {
+            cmd: words.collect::<Vec<&str>>().join(" "),
+            user,
+            working_dir,
+            env,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct RuntimeSummary {
     pub name: String,
@@ -119,11 +250,23 @@ pub struct RuntimeSummary {
     pub config: Option<ConnectionConfig>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+// Labels are serialized in a stable order, e.g. for the headless JSON output
+fn sorted_labels<S: Serializer>(
+    labels: &HashMap<String, String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    labels
+        .iter()
+        .collect::<BTreeMap<_, _>>()
+        .serialize(serializer)
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct VolumeSummary {
     pub id: String,
     pub driver: String,
     pub created: i64,
+    #[serde(serialize_with = "sorted_labels")]
     pub labels: HashMap<String, String>,
 }
 
@@ -154,12 +297,13 @@ impl<'a> From<&VolumeSummary> for Vec<Line<'a>> {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct NetworkSummary {
     pub id: String,
     pub name: String,
     pub driver: String,
     pub created: i64,
+    #[serde(serialize_with = "sorted_labels")]
     pub labels: HashMap<String, String>,
 }
 
@@ -196,31 +340,95 @@ impl<'a> From<&NetworkSummary> for Vec<Line<'a>> {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct ImageSummary {
     pub id: String,
     pub name: String,
     pub size: i64,
-    pub created: i64,
+    pub created: Option<i64>,
+    pub architecture: String,
+    pub os: String,
+}
+
+impl ImageSummary {
+    pub fn platform(&self) -> String {
+        match (self.os.as_str(), self.architecture.as_str()) {
+            (_, "") => "-".to_string(),
+            ("", arch) => arch.to_string(),
+            (os, arch) => format!("{}/{}", os, arch),
+        }
+    }
 }
 
 impl<'a> From<&ImageSummary> for Row<'a> {
     fn from(value: &ImageSummary) -> Row<'a> {
+        let platform = value.platform();
         let ImageSummary {
             id,
             name,
             size,
             created,
+            ..
         } = value.clone();
         Row::new(vec![
             id.gray(),
             name.gray(),
+            platform.gray(),
             size.format_size_i(BINARY).gray(),
-            created.age().gray(),
+            created.map(|c| c.age()).unwrap_or("-".into()).gray(),
         ])
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageDetails {
+    pub repo_tags: Vec<String>,
+    pub size: i64,
+    pub layers: usize,
+    pub architecture: String,
+    pub os: String,
+}
+
+impl ImageDetails {
+    // Parse the summary out of a Docker inspect response
+    pub fn parse(details: &str) -> Option<ImageDetails> {
+        let value: serde_json::Value = serde_json::from_str(details).ok()?;
+        Some(ImageDetails {
+            repo_tags: value
+                .get("RepoTags")?
+                .as_array()?
+                .iter()
+                .filter_map(|t| t.as_str().map(String::from))
+                .collect(),
+            size: value.get("Size")?.as_i64()?,
+            layers: value
+                .get("RootFS")
+                .and_then(|r| r.get("Layers"))
+                .and_then(|l| l.as_array())
+                .map_or(0, Vec::len),
+            architecture: value.get("Architecture")?.as_str()?.to_string(),
+            os: value.get("Os")?.as_str()?.to_string(),
+        })
+    }
+}
+
+impl<'a> From<&ImageDetails> for Vec<Line<'a>> {
+    fn from(val: &ImageDetails) -> Vec<Line<'a>> {
+        let style = Style::default().gray();
+        let tags = if val.repo_tags.is_empty() {
+            "<none>".to_string()
+        } else {
+            val.repo_tags.join(", ")
+        };
+        vec![
+            Line::styled(format!("Tags: {}", tags), style),
+            Line::styled(format!("Size: {}", val.size.format_size_i(BINARY)), style),
+            Line::styled(format!("Layers: {}", val.layers), style),
+            Line::styled(format!("Platform: {}/{}", val.os, val.architecture), style),
+        ]
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 pub enum ContainerHealth {
@@ -231,7 +439,8 @@ pub enum ContainerHealth {
 }
 
 #[allow(dead_code)]
-#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Serialize)]
+#[serde(into = "String")]
 pub enum ContainerStatus {
     Created,
     Running(ContainerHealth),
@@ -294,7 +503,7 @@ impl From<ContainerStatus> for String {
 }
 
 impl ContainerStatus {
-    fn format(&self) -> Span<'static> {
+    pub fn format(&self) -> Span<'static> {
         match self {
             ContainerStatus::Created => Span::styled("created", Style::new().dark_gray()),
             ContainerStatus::Running(h) => match h {
@@ -317,15 +526,29 @@ impl ContainerStatus {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct ContainerSummary {
     pub id: String,
     pub name: String,
     pub image: String,
     pub image_id: String,
+    #[serde(serialize_with = "sorted_labels")]
     pub labels: HashMap<String, String>,
     pub status: ContainerStatus,
     pub age: i64,
+    // Not part of the runtimes listing, only filled by the containers view showing the uptime
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started: Option<i64>,
+}
+
+impl ContainerSummary {
+    // Start time of running containers when known, creation time otherwise
+    pub fn since(&self, uptime: bool) -> i64 {
+        match (uptime, &self.status, self.started) {
+            (true, ContainerStatus::Running(_), Some(started)) => started,
+            _ => self.age,
+        }
+    }
 }
 
 impl<'a> From<&ContainerSummary> for Vec<Cell<'a>> {
@@ -352,7 +575,7 @@ fn details_to_lines<'a>(val: &ContainerDetails, indent: usize) -> Vec<Line<'a>>
     let style = Style::default().gray();
     let mut text: Vec<Line> = vec![
         Line::styled(
-            format!("{:indent$}Id: {}", "", &val.id[0..12], indent = indent).to_string(),
+            format!("{:indent$}Id: {}", "", short_id(&val.id), indent = indent).to_string(),
             style,
         ),
         Line::styled(
@@ -370,6 +593,12 @@ fn details_to_lines<'a>(val: &ContainerDetails, indent: usize) -> Vec<Line<'a>>
             style,
         ));
     }
+    if let (ContainerStatus::Running(_), Some(started)) = (&val.status, val.started) {
+        text.push(Line::styled(
+            format!("{:indent$}Started: {}", "", started.age(), indent = indent),
+            style,
+        ));
+    }
     match (val.image.as_ref(), val.image_id.as_ref()) {
         (Some(image), Some(_image_id)) => text.push(Line::styled(
             format!("{:indent$}Image: {}", "", image, indent = indent),
@@ -441,18 +670,26 @@ fn details_to_lines<'a>(val: &ContainerDetails, indent: usize) -> Vec<Line<'a>>
                 .collect(),
         );
     }
-    if !val.volumes.is_empty() {
+    if !val.mounts.is_empty() {
         text.push(Line::styled(
-            format!("{:indent$}Volumes:", "", indent = indent),
+            format!("{:indent$}Mounts:", "", indent = indent),
             style,
         ));
         text.append(
             &mut val
-                .volumes
+                .mounts
                 .iter()
-                .map(|(s, d)| {
+                .map(|m| {
                     Line::styled(
-                        format!("{:indent$}  - {}:{}", "", s, d, indent = indent),
+                        format!(
+                            "{:indent$}  - {}:{} ({}, {})",
+                            "",
+                            m.source,
+                            m.destination,
+                            m.kind,
+                            if m.rw { "rw" } else { "ro" },
+                            indent = indent
+                        ),
                         style,
                     )
                 })
@@ -502,9 +739,91 @@ fn details_to_lines<'a>(val: &ContainerDetails, indent: usize) -> Vec<Line<'a>>
             &mut val
                 .ports
                 .iter()
+                .map(|(port, host)| {
+                    let published = if host.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" -> {}", host)
+                    };
+                    Line::styled(
+                        format!("{:indent$}  - {}{}", "", port, published, indent = indent),
+                        style,
+                    )
+                })
+                .collect(),
+        );
+    }
+    text.append(&mut host_details_to_lines(&val.host, indent));
+    text
+}
+
+fn host_details_to_lines<'a>(val: &HostDetails, indent: usize) -> Vec<Line<'a>> {
+    let style = Style::default().gray();
+    let mut text = vec![
+        Line::styled(
+            format!("{:indent$}Host config:", "", indent = indent),
+            style,
+        ),
+        Line::from(vec![
+            Span::styled(
+                format!("{:indent$}  Privileged: ", "", indent = indent),
+                style,
+            ),
+            if val.privileged {
+                "yes".red().bold()
+            } else {
+                "no".gray()
+            },
+        ]),
+        Line::styled(
+            format!(
+                "{:indent$}  CPU limit: {}",
+                "",
+                val.cpus.map_or("none".to_string(), |c| format!("{:.2}", c)),
+                indent = indent
+            ),
+            style,
+        ),
+        Line::styled(
+            format!(
+                "{:indent$}  Memory limit: {}",
+                "",
+                val.memory
+                    .map_or("none".to_string(), |m| m.format_size_i(BINARY)),
+                indent = indent
+            ),
+            style,
+        ),
+    ];
+    for (title, caps) in [
+        ("Capabilities added", &val.cap_add),
+        ("Capabilities dropped", &val.cap_drop),
+    ] {
+        if !caps.is_empty() {
+            text.push(Line::styled(
+                format!(
+                    "{:indent$}  {}: {}",
+                    "",
+                    title,
+                    caps.join(", "),
+                    indent = indent
+                ),
+                style,
+            ));
+        }
+    }
+    if !val.devices.is_empty() {
+        text.push(Line::styled(
+            format!("{:indent$}  Devices:", "", indent = indent),
+            style,
+        ));
+        text.append(
+            &mut val
+                .devices
+                .iter()
                 .map(|(h, c)| {
                     Line::styled(
-                        format!("{:indent$}  - {}:{}", "", h, c, indent = indent),
+                        format!("{:indent$}    - {}:{}", "", h, c, indent = indent),
                         style,
                     )
                 })
@@ -523,13 +842,116 @@ pub struct ContainerDetails {
     pub labels: HashMap<String, String>,
     pub status: ContainerStatus,
     pub age: Option<i64>,
+    pub started: Option<i64>,
     pub ports: Vec<(String, String)>,
-    pub volumes: Vec<(String, String)>,
+    pub mounts: Vec<MountDetails>,
     pub env: Vec<(String, String)>,
     pub entrypoint: Option<Vec<String>>,
     pub command: Option<Vec<String>>,
     pub network: Vec<(String, Option<String>)>,
-    pub processes: Vec<(String, String, String)>,
+    pub processes: Vec<Process>,
+    pub host: HostDetails,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Process {
+    pub user: String,
+    pub pid: String,
+    pub cpu: String,
+    pub mem: String,
+    pub stat: String,
+    pub start: String,
+    pub command: String,
+}
+
+impl Process {
+    // The columns depend on the `ps` arguments, so they are looked up by title
+    pub fn parse(titles: &[String], process: &[String]) -> Process {
+        let column = |names: &[&str]| {
+            titles
+                .iter()
+                .position(|t| names.contains(&t.as_str()))
+                .and_then(|i| process.get(i))
+                .cloned()
+                .unwrap_or("-".to_string())
+        };
+        Process {
+            user: column(&["USER", "UID"]),
+            pid: column(&["PID"]),
+            cpu: column(&["%CPU", "C"]),
+            mem: column(&["%MEM"]),
+            stat: column(&["STAT", "S"]),
+            start: column(&["START", "STIME"]),
+            command: column(&["COMMAND", "CMD"]),
+        }
+    }
+}
+
+impl<'a> From<&Process> for Row<'a> {
+    fn from(value: &Process) -> Row<'a> {
+        let Process {
+            user,
+            pid,
+            cpu,
+            mem,
+            stat,
+            start,
+            command,
+        } = value.clone();
+        Row::new(vec![user, pid, cpu, mem, stat, start, command]).style(Style::default().gray())
+    }
+}
+
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct MountDetails {
+    pub source: String,
+    pub destination: String,
+    pub kind: String,
+    pub rw: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HostDetails {
+    pub privileged: bool,
+    pub cpus: Option<f64>,
+    pub memory: Option<i64>,
+    pub cap_add: Vec<String>,
+    pub cap_drop: Vec<String>,
+    pub devices: Vec<(String, String)>,
+}
+
+const MASK: &str = "••••••";
+
+impl ContainerDetails {
+    // Copy with the values of the environment variables configured as secrets hidden,
+    // used by every view displaying the details
+    pub fn masked(&self) -> Self {
+        let config = &config::get().containers;
+        let mut details = self.clone();
+        details
+            .env
+            .iter_mut()
+            .filter(|(k, _)| config.is_masked(k))
+            .for_each(|(_, v)| *v = MASK.to_string());
+        details
+    }
+
+    // Address of the container on its first network
+    pub fn primary_ip(&self) -> Option<&str> {
+        self.network
+            .iter()
+            .filter_map(|(_, ip)| ip.as_deref())
+            .find(|ip| !ip.is_empty())
+    }
+
+    // Host port of the first published port
+    pub fn published_port(&self) -> Option<&str> {
+        self.ports
+            .iter()
+            .find(|(_, host)| !host.is_empty())
+            .and_then(|(_, host)| host.rsplit_once(':'))
+            .map(|(_, port)| port)
+    }
 }
 
 impl<'a> From<&ContainerDetails> for Vec<Line<'a>> {
@@ -538,13 +960,76 @@ impl<'a> From<&ContainerDetails> for Vec<Line<'a>> {
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum FileChangeKind {
+    Modified,
+    Added,
+    Deleted,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileChange {
+    pub path: String,
+    pub kind: FileChangeKind,
+}
+
+impl<'a> From<&FileChange> for Line<'a> {
+    fn from(value: &FileChange) -> Line<'a> {
+        let (marker, style) = match value.kind {
+            FileChangeKind::Added => ("A", Style::new().green()),
+            FileChangeKind::Modified => ("C", Style::new().yellow()),
+            FileChangeKind::Deleted => ("D", Style::new().red()),
+        };
+        Line::from(vec![
+            Span::styled(format!("{} ", marker), style.bold()),
+            Span::styled(value.path.to_string(), style),
+        ])
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Event {
+    // Unix timestamp in seconds
+    pub time: i64,
+    pub kind: String,
+    pub action: String,
+    pub actor: String,
+}
+
+impl<'a> From<&Event> for Line<'a> {
+    fn from(value: &Event) -> Line<'a> {
+        let style = match value.kind.as_str() {
+            "container" => Style::new().cyan(),
+            "image" => Style::new().magenta(),
+            "network" => Style::new().blue(),
+            "volume" => Style::new().yellow(),
+            _ => Style::new().gray(),
+        };
+        let time = DateTime::from_timestamp(value.time, 0)
+            .map(|t| {
+                t.with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        Line::from(vec![
+            Span::styled(format!("{} ", time), Style::new().dark_gray()),
+            Span::styled(format!("{:<10}", value.kind), style),
+            Span::styled(format!("{:<12}", value.action), style.bold()),
+            Span::raw(value.actor.to_string()),
+        ])
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Compose {
     pub project: String,
     pub config_file: Option<String>,
     pub working_dir: Option<String>,
     pub environment_files: Option<String>,
-    pub services: HashMap<(String, String), ContainerDetails>,
+    pub services: HashMap<(String, String), ContainerSummary>,
+    // Details of the services, only fetched for the ones displayed
+    pub details: HashMap<(String, String), ContainerDetails>,
     pub volumes: HashMap<String, VolumeSummary>,
     pub networks: HashMap<String, NetworkSummary>,
 }
@@ -562,54 +1047,68 @@ impl Compose {
             working_dir,
             environment_files,
             services: HashMap::new(),
+            details: HashMap::new(),
             volumes: HashMap::new(),
             networks: HashMap::new(),
         }
     }
-}
 
-impl<'a> From<&Compose> for Row<'a> {
-    fn from(value: &Compose) -> Row<'a> {
-        Row::new(vec![
-            value.project.to_string(),
-            value.services.len().to_string(),
-            value.volumes.len().to_string(),
-            value.networks.len().to_string(),
-        ])
+    // Sorted by service then container number, numerically so the 10th comes after the 9th
+    pub fn service_keys(&self) -> Vec<&(String, String)> {
+        let mut keys: Vec<&(String, String)> = self.services.keys().collect();
+        keys.sort_by_key(|(service, number)| (service, number.parse::<u64>().ok(), number));
+        keys
     }
-}
 
-impl<'a> From<&Compose> for Vec<Line<'a>> {
-    fn from(val: &Compose) -> Self {
-        let mut text = vec![Line::from(format!("Compose project: {}", val.project))];
-        if let Some(config_file) = &val.config_file {
+    // Services are rendered as tree nodes, only the expanded ones show their details.
+    // Also returns the line index of the selected node.
+    pub fn tree_lines<'a>(
+        &self,
+        expanded: &HashSet<(String, String)>,
+        selected: Option<usize>,
+    ) -> (Vec<Line<'a>>, usize) {
+        let mut text = vec![Line::from(format!("Compose project: {}", self.project))];
+        if let Some(config_file) = &self.config_file {
             text.push(Line::from(format!("Config file: {}", config_file)));
         }
-        if let Some(working_dir) = &val.working_dir {
+        if let Some(working_dir) = &self.working_dir {
             text.push(Line::from(format!("Working directory: {}", working_dir)));
         }
-        if let Some(env_file) = &val.environment_files {
+        if let Some(env_file) = &self.environment_files {
             text.push(Line::from(format!("Environment file: {}", env_file)));
         }
-        if !val.services.is_empty() {
+        let mut selected_line = 0;
+        if !self.services.is_empty() {
             text.push(Line::from("Services:".to_string()));
-            let mut svc_text = val
-                .services
-                .iter()
-                .flat_map(|((svc, num), c)| {
-                    let mut svc_text = vec![Line::from(format!("  {} - {}", svc, num))];
-                    let mut svc_content = details_to_lines(c, 4);
-                    svc_text.append(&mut svc_content);
-                    svc_text
-                })
-                .collect();
-            text.append(&mut svc_text);
+            for (i, key) in self.service_keys().into_iter().enumerate() {
+                let (svc, num) = key;
+                let is_expanded = expanded.contains(key);
+                let node = format!(
+                    "  {} {} - {}",
+                    if is_expanded { "▾" } else { "▸" },
+                    svc,
+                    num
+                );
+                if selected == Some(i) {
+                    selected_line = text.len();
+                    text.push(Line::styled(node, Style::default().reversed()));
+                } else {
+                    text.push(Line::from(node));
+                }
+                if is_expanded {
+                    match self.details.get(key) {
+                        Some(c) => text.append(&mut details_to_lines(&c.masked(), 6)),
+                        None => text.push(Line::from(format!("{:6}Loading...", ""))),
+                    }
+                }
+            }
         }
-        if !val.networks.is_empty() {
+        if !self.networks.is_empty() {
             text.push(Line::from("Networks:".to_string()));
-            let mut net_text = val
-                .networks
-                .iter()
+            let mut networks: Vec<(&String, &NetworkSummary)> = self.networks.iter().collect();
+            networks.sort_by_key(|(name, _)| *name);
+            let mut net_text = networks
+                .into_iter()
                 .flat_map(|(name, net)| {
                     let mut net_text = vec![Line::from(format!("- Name: {}", name))];
                     let mut net_content = net.into();
@@ -619,11 +1118,12 @@ impl<'a> From<&Compose> for Vec<Line<'a>> {
                 .collect();
             text.append(&mut net_text);
         }
-        if !val.volumes.is_empty() {
+        if !self.volumes.is_empty() {
             text.push(Line::from("Volumes:".to_string()));
-            let mut vol_text = val
-                .volumes
-                .iter()
+            let mut volumes: Vec<(&String, &VolumeSummary)> = self.volumes.iter().collect();
+            volumes.sort_by_key(|(id, _)| *id);
+            let mut vol_text = volumes
+                .into_iter()
                 .flat_map(|(id, vol)| {
                     let mut vol_text = vec![Line::from(format!("- Id: {}", id))];
                     let mut vol_content = vol.into();
@@ -634,7 +1134,25 @@ impl<'a> From<&Compose> for Vec<Line<'a>> {
             text.append(&mut vol_text);
         }
 
-        text
+        (text, selected_line)
+    }
+}
+
+impl<'a> From<&Compose> for Row<'a> {
+    fn from(value: &Compose) -> Row<'a> {
+        Row::new(vec![
+            value.project.to_string(),
+            value.services.len().to_string(),
+            value.volumes.len().to_string(),
+            value.networks.len().to_string(),
+        ])
+    }
+}
+
+impl<'a> From<&Compose> for Vec<Line<'a>> {
+    fn from(val: &Compose) -> Self {
+        let expanded = val.services.keys().cloned().collect();
+        val.tree_lines(&expanded, None).0
     }
 }
 
@@ -644,9 +1162,27 @@ pub struct ContainerMetrics {
     cid: String,
     cpu: VecDeque<f64>,
     mem: VecDeque<u64>,
+    net: VecDeque<(f64, f64)>,
+    blkio: VecDeque<(f64, f64)>,
+    last_io: Option<IoSample>,
     capacity: usize,
 }
 
+/// Cumulative I/O counters of a stats sample, used to compute rates
+#[derive(Clone, Debug)]
+struct IoSample {
+    read_time: i64,
+    net: Option<(u64, u64)>,
+    blkio: Option<(u64, u64)>,
+}
+
+fn compute_rate(previous: (u64, u64), current: (u64, u64), seconds: f64) -> (f64, f64) {
+    (
+        current.0.saturating_sub(previous.0) as f64 / seconds,
+        current.1.saturating_sub(previous.1) as f64 / seconds,
+    )
+}
+
 impl ContainerMetrics {
     pub fn new(cid: String, capacity: usize) -> Self {
         ContainerMetrics {
@@ -654,6 +1190,9 @@ impl ContainerMetrics {
             capacity,
             cpu: VecDeque::with_capacity(capacity),
             mem: VecDeque::with_capacity(capacity),
+            net: VecDeque::with_capacity(capacity),
+            blkio: VecDeque::with_capacity(capacity),
+            last_io: None,
         }
     }
 
@@ -672,6 +1211,44 @@ impl ContainerMetrics {
         }
     }
 
+    /// Store the I/O rates computed from the previous sample, the first sample
+    /// is only kept as a reference as it has no predecessor
+    pub fn push_io(
+        &mut self,
+        read_time: Option<i64>,
+        net: Option<(u64, u64)>,
+        blkio: Option<(u64, u64)>,
+    ) {
+        let Some(read_time) = read_time else {
+            return;
+        };
+        if let Some(last) = self.last_io.take() {
+            let elapsed = read_time.saturating_sub(last.read_time);
+            if elapsed > 0 {
+                let seconds = elapsed as f64 / 1000.0;
+                if let (Some(previous), Some(current)) = (last.net, net) {
+                    if self.net.len() >= self.capacity {
+                        let _ = self.net.pop_back();
+                    }
+                    self.net
+                        .push_front(compute_rate(previous, current, seconds));
+                }
+                if let (Some(previous), Some(current)) = (last.blkio, blkio) {
+                    if self.blkio.len() >= self.capacity {
+                        let _ = self.blkio.pop_back();
+                    }
+                    self.blkio
+                        .push_front(compute_rate(previous, current, seconds));
+                }
+            }
+        }
+        self.last_io = Some(IoSample {
+            read_time,
+            net,
+            blkio,
+        });
+    }
+
     pub fn cpu_data(&self) -> impl Iterator<Item = &f64> {
         self.cpu.iter()
     }
@@ -679,4 +1256,166 @@ impl ContainerMetrics {
     pub fn mem_data(&self) -> impl Iterator<Item = &u64> {
         self.mem.iter()
     }
+
+    pub fn net_data(&self) -> impl Iterator<Item = &(f64, f64)> {
+        self.net.iter()
+    }
+
+    pub fn blkio_data(&self) -> impl Iterator<Item = &(f64, f64)> {
+        self.blkio.iter()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+// Name displayed for a container, its short id when the runtime gives no usable name
+// (anonymous CRI containers, containers still being created...)
+pub fn container_name(name: Option<&str>, id: &str) -> String {
+    match name.map(|n| n.trim_start_matches('/')) {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => short_id(id).to_string(),
+    }
+}
+
+pub fn min_max_avg(data: impl Iterator<Item = f64>) -> Option<(f64, f64, f64)> {
+    let (min, max, sum, count) = data.fold(
+        (f64::MAX, f64::MIN, 0.0, 0usize),
+        |(min, max, sum, count), v| (min.min(v), max.max(v), sum + v, count + 1),
+    );
+    if count > 0 {
+        Some((min, max, sum / count as f64))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels() -> HashMap<String, String> {
+        (0..5)
+            .map(|i| (format!("label.{}", i), i.to_string()))
+            .collect()
+    }
+
+    fn compose() -> Compose {
+        let mut compose = Compose::new("demo".to_string(), None, None, None);
+        for (service, number) in [
+            ("web", "10"),
+            ("db", "1"),
+            ("web", "2"),
+            ("web", "1"),
+            ("cache", "1"),
+        ] {
+            compose.services.insert(
+                (service.to_string(), number.to_string()),
+                ContainerSummary {
+                    id: format!("{}-{}", service, number),
+                    name: format!("demo-{}-{}", service, number),
+                    image: service.to_string(),
+                    image_id: service.to_string(),
+                    labels: labels(),
+                    status: ContainerStatus::Exited,
+                    age: 0,
+                    started: None,
+                },
+            );
+        }
+        for name in ["front", "back", "default"] {
+            compose.networks.insert(
+                name.to_string(),
+                NetworkSummary {
+                    id: name.to_string(),
+                    name: name.to_string(),
+                    driver: "bridge".to_string(),
+                    created: 0,
+                    labels: labels(),
+                },
+            );
+        }
+        for id in ["data", "cache", "logs"] {
+            compose.volumes.insert(
+                id.to_string(),
+                VolumeSummary {
+                    id: id.to_string(),
+                    driver: "local".to_string(),
+                    created: 0,
+                    labels: labels(),
+                },
+            );
+        }
+        compose
+    }
+
+    fn text(lines: &[Line]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn compose_lines_are_stable() {
+        let lines: Vec<Line> = (&compose()).into();
+        assert_eq!(
+            text(&lines),
+            vec![
+                "Compose project: demo",
+                "Services:",
+                "  ▾ cache - 1",
+                "      Loading...",
+                "  ▾ db - 1",
+                "      Loading...",
+                "  ▾ web - 1",
+                "      Loading...",
+                "  ▾ web - 2",
+                "      Loading...",
+                "  ▾ web - 10",
+                "      Loading...",
+                "Networks:",
+                "- Name: back",
+                "  Driver: bridge",
+                "- Name: default",
+                "  Driver: bridge",
+                "- Name: front",
+                "  Driver: bridge",
+                "Volumes:",
+                "- Id: cache",
+                "  Driver: local",
+                "- Id: data",
+                "  Driver: local",
+                "- Id: logs",
+                "  Driver: local",
+            ]
+        );
+        // Each map is seeded differently, the output must not depend on their iteration order
+        for _ in 0..10 {
+            let other: Vec<Line> = (&compose()).into();
+            assert_eq!(text(&other), text(&lines));
+        }
+    }
+
+    #[test]
+    fn labels_are_serialized_sorted() {
+        let compose = compose();
+        let expected = format!(
+            "\"labels\":{{{}}}",
+            (0..5)
+                .map(|i| format!("\"label.{}\":\"{}\"", i, i))
+                .collect::<Vec<String>>()
+                .join(",")
+        );
+        for service in compose.services.values() {
+            assert!(serde_json::to_string(service).unwrap().contains(&expected));
+        }
+        for network in compose.networks.values() {
+            assert!(serde_json::to_string(network).unwrap().contains(&expected));
+        }
+        for volume in compose.volumes.values() {
+            assert!(serde_json::to_string(volume).unwrap().contains(&expected));
+        }
+    }
 }
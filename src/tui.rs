@@ -7,7 +7,10 @@ use color_eyre::Result;
 
 use crossterm::{
     cursor,
-    event::{Event as CrosstermEvent, KeyEvent, KeyEventKind},
+    event::{
+        DisableBracketedPaste, EnableBracketedPaste, Event as CrosstermEvent, KeyEvent,
+        KeyEventKind,
+    },
     terminal::{EnterAlternateScreen, LeaveAlternateScreen},
 };
 
@@ -25,6 +28,8 @@ pub enum Event {
     Tick,
     Render,
     Key(KeyEvent),
+    // Text pasted in the terminal, received at once instead of one key event per char
+    Paste(String),
     Resize(u16, u16),
     Error,
     Init,
@@ -93,10 +98,11 @@ impl Tui {
                     match maybe_event {
                       Some(Ok(evt)) => {
                         match evt {
-                          CrosstermEvent::Key(key) => {
-                            if key.kind == KeyEventKind::Press {
-                              _event_tx.send(Event::Key(key)).unwrap();
-                            }
+                          CrosstermEvent::Key(key) if key.kind == KeyEventKind::Press => {
+                            _event_tx.send(Event::Key(key)).unwrap();
+                          },
+                          CrosstermEvent::Paste(text) => {
+                            _event_tx.send(Event::Paste(text)).unwrap();
                           },
                           CrosstermEvent::Resize(x, y) => {
                             _event_tx.send(Event::Resize(x, y)).unwrap();
@@ -140,14 +146,24 @@ impl Tui {
 
     pub fn enter(&mut self) -> Result<()> {
         crossterm::terminal::enable_raw_mode()?;
-        crossterm::execute!(std::io::stderr(), EnterAlternateScreen, cursor::Hide)?;
+        crossterm::execute!(
+            std::io::stderr(),
+            EnterAlternateScreen,
+            EnableBracketedPaste,
+            cursor::Hide
+        )?;
         self.start();
         Ok(())
     }
 
     pub fn exit(&mut self) -> Result<()> {
         if crossterm::terminal::is_raw_mode_enabled()? {
-            crossterm::execute!(std::io::stderr(), LeaveAlternateScreen, cursor::Show)?;
+            crossterm::execute!(
+                std::io::stderr(),
+                DisableBracketedPaste,
+                LeaveAlternateScreen,
+                cursor::Show
+            )?;
             crossterm::terminal::disable_raw_mode()?;
         }
         Ok(())
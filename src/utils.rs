@@ -1,24 +1,48 @@
-use std::{path::PathBuf, rc::Rc};
+use std::{
+    collections::{HashSet, VecDeque},
+    future::Future,
+    io::Write,
+    path::PathBuf,
+    rc::Rc,
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
 
-use chrono::{TimeZone, Utc};
+use chrono::{DateTime, Local, TimeZone, Utc};
 use color_eyre::Result;
 
 use directories::ProjectDirs;
+use futures::future::join_all;
+use humansize::{format_size, FormatSizeOptions, BINARY};
 use lazy_static::lazy_static;
 
 #[cfg(feature = "otel")]
-use opentelemetry::global;
+use opentelemetry::{
+    global,
+    sdk::{propagation::TraceContextPropagator, trace, Resource},
+    KeyValue,
+};
+#[cfg(feature = "otel")]
+use opentelemetry_otlp::WithExportConfig;
 
+use tokio::sync::mpsc::UnboundedSender;
 use tracing::error;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
 use tracing_error::ErrorLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Layer};
 
+use crate::action::Action;
 use crate::components::Component;
+use crate::config;
+use crate::runtime::{list_containers, Filter, NameFilter};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 use ratatui::{
     prelude::*,
     widgets::{
-        block::Title, Block, Borders, Cell, Clear, LineGauge, Padding, Paragraph, Row, Table, Wrap,
+        block::Title, Block, Borders, Cell, Clear, LineGauge, Padding, Paragraph, Row, Scrollbar,
+        ScrollbarOrientation, ScrollbarState, Table, Wrap,
     },
 };
 
@@ -35,14 +59,26 @@ lazy_static! {
             .ok()
             .map(PathBuf::from);
     pub static ref LOG_ENV: String = format!("{}_LOGLEVEL", PROJECT_NAME.clone());
-    pub static ref LOG_FILE: String = format!("{}.log", env!("CARGO_PKG_NAME"));
 }
 
-const GENERAL_BINDINGS: [(&str, &str); 5] = [
+const GENERAL_BINDINGS: [(&str, &str); 15] = [
     ("q", "Quit"),
     (":", "Change resource"),
+    ("Tab", "Next resource"),
+    ("Shift+Tab", "Previous resource"),
+    (
+        "g+c/i/v/n/p",
+        "Go to containers/images/volumes/networks/composes",
+    ),
+    ("1-5", "Go to containers/images/volumes/networks/composes"),
+    ("gg", "Go to first row"),
+    ("G", "Go to last row"),
     ("/", "Filter resources"),
+    ("b", "Filter presets"),
     ("?", "Help"),
+    ("ctrl+p", "Command palette"),
+    ("E", "Error log"),
+    ("r", "Refresh now"),
     ("ESC", "Cancel/Previous screen"),
 ];
 
@@ -59,6 +95,27 @@ fn project_directory() -> Option<ProjectDirs> {
 
 pub const NONE: &str = "<none>";
 
+// Characters from `start` (included) to `end` (excluded) of a string, both positions are
+// counted in chars and clamped to its length so it never panics
+pub fn safe_subslice(s: &str, start: usize, end: usize) -> &str {
+    let start = byte_index(s, start);
+    let end = byte_index(s, end).max(start);
+    &s[start..end]
+}
+
+// Byte index of the char at `position`, or the length of the string past its end
+pub(crate) fn byte_index(s: &str, position: usize) -> usize {
+    s.char_indices()
+        .nth(position)
+        .map(|(index, _)| index)
+        .unwrap_or(s.len())
+}
+
+// First 12 characters of an id, or the whole id when it is shorter
+pub fn short_id(id: &str) -> &str {
+    safe_subslice(id, 0, 12)
+}
+
 macro_rules! get_or_not_found {
     ($property:expr) => {
         $property
@@ -78,27 +135,367 @@ macro_rules! get_or_not_found {
 
 pub(crate) use get_or_not_found;
 
-pub(crate) fn table<'a, const SIZE: usize>(
+// `sorted` is the index of the column the rows are sorted by, and whether the order is ascending
+pub(crate) fn table<'a>(
     title: String,
-    headers: [&'a str; SIZE],
+    headers: &[&'a str],
     items: Vec<Row<'a>>,
-    constraints: &'static [Constraint; SIZE],
+    constraints: &[Constraint],
     style: Option<Style>,
+    sorted: Option<(usize, bool)>,
 ) -> Table<'a> {
     let normal_style = style.unwrap_or_default();
     let selected_style = normal_style.reversed();
-    let header_cells = headers
-        .iter()
-        .map(|h| Cell::from(*h).style(Style::default().bold()));
+    let header_cells = headers.iter().enumerate().map(|(i, h)| {
+        let header = match sorted {
+            Some((column, true)) if column == i => format!("{} ▲", h),
+            Some((column, false)) if column == i => format!("{} ▼", h),
+            _ => h.to_string(),
+        };
+        Cell::from(header).style(Style::default().bold())
+    });
     let header = ratatui::widgets::Row::new(header_cells)
         .style(normal_style)
         .height(1);
-    Table::new(items, constraints)
+    Table::new(items, constraints.to_vec())
         .header(header)
         .block(Block::default().borders(Borders::ALL).title(title))
         .highlight_style(selected_style)
 }
 
+pub(crate) fn confirmation_popup(f: &mut Frame<'_>, question: Line<'_>) {
+    let text = vec![
+        question,
+        Line::from(""),
+        Line::from(vec![
+            "ESC".bold(),
+            " to Cancel, ".into(),
+            "Enter".bold(),
+            " to Confirm".into(),
+        ]),
+    ];
+    let paragraph = Paragraph::new(text).wrap(Wrap { trim: false });
+
+    let block = Block::default()
+        .title("Confirmation".bold())
+        .padding(Padding::new(1, 1, 1, 1))
+        .borders(Borders::ALL);
+    let area = centered_rect(50, 8, f.size());
+    f.render_widget(Clear, area); //this clears out the background
+    f.render_widget(paragraph.block(block), area);
+}
+
+// Confirmation of the deletion of a resource, listing the containers using it
+pub(crate) fn delete_popup(f: &mut Frame<'_>, resource: &str, name: &str, used_by: &[String]) {
+    let mut text = vec![
+        Line::from(vec![
+            Span::raw(format!("Are you sure you want to delete {}: \"", resource)),
+            Span::styled(name.to_string(), Style::new().gray()),
+            Span::raw("\"?"),
+        ]),
+        Line::from(""),
+    ];
+    if !used_by.is_empty() {
+        text.push(Line::styled(
+            format!(
+                "It is used by {} containers: {}",
+                used_by.len(),
+                used_by.join(", ")
+            ),
+            Style::new().red(),
+        ));
+        text.push(Line::from(""));
+    }
+    text.push(Line::from(vec![
+        "ESC".bold(),
+        " to Cancel, ".into(),
+        "Enter".bold(),
+        " to Confirm".into(),
+    ]));
+    let paragraph = Paragraph::new(text).wrap(Wrap { trim: false });
+
+    let block = Block::default()
+        .title("Confirmation".bold())
+        .padding(Padding::new(1, 1, 1, 1))
+        .borders(Borders::ALL);
+    let width = centered_rect(50, 8, f.size()).width;
+    let height = paragraph.line_count(width.saturating_sub(4)) as u16 + 4;
+    let area = centered_rect(50, height.max(8), f.size());
+    f.render_widget(Clear, area); //this clears out the background
+    f.render_widget(paragraph.block(block), area);
+}
+
+// Names of the containers, running or not, matching the filter (e.g. `volume=<name>`)
+pub(crate) async fn containers_using(filter: &Filter) -> Vec<String> {
+    match list_containers(true, filter).await {
+        Ok(containers) => containers.into_iter().map(|c| c.name).collect(),
+        Err(e) => {
+            log::warn!("Unable to list the containers using {:?}: {}", filter, e);
+            vec![]
+        }
+    }
+}
+
+// Destructive actions open a confirmation popup, unless disabled in the configuration:
+// the popup is then confirmed right away.
+pub(crate) fn request_confirmation(tx: &UnboundedSender<Action>) -> Result<()> {
+    if !config::get().ui.confirm_destructive {
+        tx.send(Action::Ok)?;
+    }
+    Ok(())
+}
+
+// Runs the deletions concurrently, returns how many were attempted and the failures
+pub(crate) async fn delete_all<F, Fut>(ids: Vec<String>, delete: F) -> (usize, Vec<String>)
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let count = ids.len();
+    let errors = join_all(ids.into_iter().map(|id| {
+        let deletion = delete(id.clone());
+        async move { deletion.await.err().map(|e| format!("{}: {}", id, e)) }
+    }))
+    .await
+    .into_iter()
+    .flatten()
+    .collect();
+    (count, errors)
+}
+
+// Deletes the selected resources, meant to be spawned so the UI is not blocked meanwhile
+pub(crate) async fn delete_selected<F, Fut>(
+    resource: &'static str,
+    ids: Vec<String>,
+    delete: F,
+    tx: UnboundedSender<Action>,
+) -> Result<()>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let (count, errors) = delete_all(ids, delete).await;
+    if !errors.is_empty() {
+        tx.send(Action::Error(format!(
+            "Unable to delete {} of {} {}:\n{}",
+            errors.len(),
+            count,
+            resource,
+            errors.join("\n")
+        )))?;
+    }
+    tx.send(Action::Tick)?;
+    Ok(())
+}
+
+// Marks or unmarks the resource under the cursor, returns false when there is none
+pub(crate) fn toggle_selection(selected: &mut HashSet<String>, id: Option<&String>) -> bool {
+    match id {
+        Some(id) => {
+            if !selected.remove(id) {
+                selected.insert(id.clone());
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+// Forgets the selected resources which are not listed anymore
+pub(crate) fn retain_listed<'a>(
+    selected: &mut HashSet<String>,
+    listed: impl Iterator<Item = &'a String>,
+) {
+    let listed: HashSet<&String> = listed.collect();
+    selected.retain(|id| listed.contains(id));
+}
+
+pub(crate) fn selection_style(row: Row<'_>, selected: bool) -> Row<'_> {
+    if selected {
+        row.style(Style::new().yellow())
+    } else {
+        row
+    }
+}
+
+pub(crate) fn empty_state(f: &mut Frame<'_>, area: Rect, resource: &str, filters: &[String]) {
+    let filters: Vec<&str> = filters
+        .iter()
+        .map(|s| s.as_str())
+        .filter(|s| !s.is_empty())
+        .collect();
+    let message = if filters.is_empty() {
+        format!("No {} found", resource)
+    } else {
+        format!("No {} match filter {}", resource, filters.join(" "))
+    };
+    let rects = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(50),
+            Constraint::Length(1),
+            Constraint::Percentage(50),
+        ])
+        .split(area.inner(&Margin::new(1, 2)));
+    f.render_widget(
+        Paragraph::new(message).alignment(Alignment::Center).gray(),
+        rects[1],
+    );
+}
+
+pub(crate) fn format_rates(input: f64, output: f64) -> String {
+    let format = FormatSizeOptions::from(BINARY)
+        .decimal_places(1)
+        .space_after_value(false);
+    format!(
+        "{}/s / {}/s",
+        format_size(input as u64, format),
+        format_size(output as u64, format)
+    )
+}
+
+pub(crate) fn table_page_size(area: Rect) -> usize {
+    // Borders and header take 3 lines
+    usize::from(area.height.saturating_sub(3)).max(1)
+}
+
+// Below this size the main layout cannot fit the header, a table and the status bar
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 10;
+
+pub fn is_too_small(size: Rect) -> bool {
+    size.width < MIN_WIDTH || size.height < MIN_HEIGHT
+}
+
+pub fn too_small_screen(f: &mut Frame<'_>, size: Rect) {
+    let text = vec![
+        Line::styled("Terminal too small", Style::default().bold()),
+        Line::from(format!("{}x{}", size.width, size.height)),
+        Line::from(format!("Needs {}x{}", MIN_WIDTH, MIN_HEIGHT)),
+    ];
+    let area = centered_rect(size.width, 3, size);
+    f.render_widget(
+        Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true }),
+        area,
+    );
+}
+
+// Displayed until the runtime answers, or with the error when the connection failed
+pub(crate) fn connecting_screen(
+    f: &mut Frame<'_>,
+    target: &str,
+    spinner: char,
+    error: Option<&str>,
+) {
+    let width = 60.min(f.size().width);
+    let (title, mut text) = match error {
+        None => (
+            "Connecting",
+            vec![Line::from(format!(
+                "{} Connecting to {}...",
+                spinner, target
+            ))],
+        ),
+        Some(e) => (
+            "Connection failed",
+            vec![
+                Line::from(format!("Unable to connect to {}:", target)),
+                Line::from(""),
+                Line::styled(e.to_string(), Style::new().red()),
+            ],
+        ),
+    };
+    text.push(Line::from(""));
+    text.push(Line::from(vec![
+        "q".bold(),
+        " or ".into(),
+        "ESC".bold(),
+        " to quit".into(),
+    ]));
+    let paragraph = Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .alignment(Alignment::Center);
+    let height = u16::try_from(paragraph.line_count(width.saturating_sub(4)))
+        .unwrap_or(u16::MAX)
+        .saturating_add(4);
+    let block = Block::default()
+        .title(title.bold())
+        .padding(Padding::new(1, 1, 1, 1))
+        .borders(Borders::ALL);
+    let area = centered_rect(width, height, f.size());
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph.block(block), area);
+}
+
+pub(crate) fn runtime_picker_screen(f: &mut Frame<'_>, runtimes: &[String], selected: usize) {
+    select_popup(
+        f,
+        "Runtime",
+        "Several runtimes were found, which one should be used?",
+        runtimes,
+        selected,
+        Line::from(vec![
+            "Up/Down".bold(),
+            " to select, ".into(),
+            "Enter".bold(),
+            " to connect, ".into(),
+            "q".bold(),
+            " to quit".into(),
+        ]),
+    );
+}
+
+pub(crate) fn presets_popup(f: &mut Frame<'_>, presets: &[String], selected: usize) {
+    select_popup(
+        f,
+        "Filter presets",
+        "Which filter should be applied?",
+        presets,
+        selected,
+        Line::from(vec![
+            "Up/Down".bold(),
+            " to select, ".into(),
+            "Enter".bold(),
+            " to apply, ".into(),
+            "ESC".bold(),
+            " to cancel".into(),
+        ]),
+    );
+}
+
+fn select_popup(
+    f: &mut Frame<'_>,
+    title: &str,
+    prompt: &str,
+    items: &[String],
+    selected: usize,
+    keys: Line<'_>,
+) {
+    let mut text = vec![Line::from(prompt.to_string())];
+    text.push(Line::from(""));
+    text.extend(items.iter().enumerate().map(|(i, item)| {
+        if i == selected {
+            Line::styled(format!("> {}", item), Style::new().reversed())
+        } else {
+            Line::from(format!("  {}", item))
+        }
+    }));
+    text.push(Line::from(""));
+    text.push(keys);
+    let height = u16::try_from(text.len())
+        .unwrap_or(u16::MAX)
+        .saturating_add(4);
+    let block = Block::default()
+        .title(title.bold())
+        .padding(Padding::new(1, 1, 1, 1))
+        .borders(Borders::ALL);
+    let area = centered_rect(70.min(f.size().width), height, f.size());
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(text).block(block), area);
+}
+
 pub fn default_layout(size: Rect) -> Rc<[Rect]> {
     Layout::default()
         .direction(Direction::Vertical)
@@ -110,24 +507,31 @@ pub fn centered_rect(size_x: u16, size_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Max((r.height - size_y) / 2),
+            Constraint::Max(r.height.saturating_sub(size_y) / 2),
             Constraint::Min(size_y),
-            Constraint::Max((r.height - size_y) / 2),
+            Constraint::Max(r.height.saturating_sub(size_y) / 2),
         ])
         .split(r);
 
     Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Max((r.width - size_x) / 2),
+            Constraint::Max(r.width.saturating_sub(size_x) / 2),
             Constraint::Min(size_x),
-            Constraint::Max((r.width - size_x) / 2),
+            Constraint::Max(r.width.saturating_sub(size_x) / 2),
         ])
         .split(popup_layout[1])[1]
 }
 
-pub fn toast<'a, T>(f: &mut Frame<'_>, title: T, msg: &str, timeout: usize, ttl: usize)
-where
+pub fn toast<'a, T>(
+    f: &mut Frame<'_>,
+    title: T,
+    msg: &str,
+    timeout: usize,
+    ttl: usize,
+    paused: bool,
+    scroll: &mut usize,
+) where
     T: Into<Title<'a>>,
 {
     let width = 60;
@@ -144,19 +548,35 @@ where
         .line_set(symbols::line::THICK)
         .ratio(((timeout - ttl) as f64) / (timeout as f64));
 
-    let text = vec![
-        Line::from(msg),
-        Line::from(""),
-        Line::from(vec![
-            Span::from("Press "),
-            Span::styled("ESC", Style::new().bold()),
-            Span::from(" to cancel"),
-        ]),
+    let message = Paragraph::new(msg)
+        .wrap(Wrap { trim: false })
+        .alignment(Alignment::Center);
+    let line_count: u16 = message
+        .line_count(width - 4)
+        .try_into()
+        .expect("Too much lines");
+    // Keep room for the borders, the padding and the help line
+    let max_lines = f.size().height.saturating_sub(10).max(1);
+    let visible_lines = line_count.min(max_lines);
+    let max_scroll = usize::from(line_count - visible_lines);
+    *scroll = (*scroll).min(max_scroll);
+
+    let mut help = vec![
+        Span::from("Press "),
+        Span::styled("ESC", Style::new().bold()),
+        Span::from(" to cancel, "),
+        Span::styled("SPACE", Style::new().bold()),
+        Span::from(if paused { " to resume" } else { " to pause" }),
     ];
-    let paragraph = Paragraph::new(text)
+    if max_scroll > 0 {
+        help.push(Span::from(", "));
+        help.push(Span::styled("PageUp/PageDown", Style::new().bold()));
+        help.push(Span::from(" to scroll"));
+    }
+    let help = Paragraph::new(Line::from(help))
         .wrap(Wrap { trim: false })
         .alignment(Alignment::Center);
-    let line_count: u16 = paragraph
+    let help_lines: u16 = help
         .line_count(width - 4)
         .try_into()
         .expect("Too much lines");
@@ -165,71 +585,273 @@ where
         .title(title)
         .padding(Padding::new(1, 1, 1, 1))
         .borders(Borders::ALL);
-    let area = centered_rect(width, line_count + 4, f.size());
+    let area = centered_rect(width, visible_lines + help_lines + 5, f.size());
     let pg_area = Rect::new(
         area.x,
-        area.y + area.height - 2,
-        area.width - 1,
+        area.y + area.height.saturating_sub(2),
+        area.width.saturating_sub(1),
         area.height,
     );
+    let rects = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(visible_lines),
+            Constraint::Length(1),
+            Constraint::Length(help_lines),
+        ])
+        .split(block.inner(area));
 
     f.render_widget(Clear, area); //this clears out the background
-    f.render_widget(paragraph.block(block), area);
+    f.render_widget(block, area);
+    f.render_widget(message.scroll((*scroll as u16, 0)), rects[0]);
+    if max_scroll > 0 {
+        let mut scrollbar_state = ScrollbarState::new(max_scroll).position(*scroll);
+        f.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight),
+            rects[0],
+            &mut scrollbar_state,
+        );
+    }
+    f.render_widget(help, rects[2]);
     f.render_widget(lg, pg_area);
 }
 
-pub(crate) fn help_screen(f: &mut Frame<'_>, component: &Component) {
+pub(crate) fn help_screen<F>(
+    f: &mut Frame<'_>,
+    component: &Component,
+    search: &str,
+    scroll: &mut usize,
+    is_disabled: F,
+) where
+    F: Fn(&str) -> bool,
+{
     let area = default_layout(f.size())[1];
 
     let block = Block::default()
-        .title("Help")
-        .padding(Padding::new(1, 1, 1, 1))
+        .title("Help (type to search, Up/Down/PageUp/PageDown to scroll, ESC to close)")
+        .padding(Padding::new(1, 1, 0, 0))
         .borders(Borders::ALL);
+    let inner = block.inner(area);
 
-    let columns = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(33),
-            Constraint::Percentage(33),
-            Constraint::Percentage(33),
-        ])
-        .split(area);
+    let rects = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(1)])
+        .split(inner);
 
-    let column_block = Block::default()
-        .padding(Padding::new(1, 1, 1, 1))
-        .borders(Borders::NONE);
+    let search_line = Line::from(vec![
+        Span::styled("Search: ", Style::new().bold()),
+        Span::raw(search.to_string()),
+        Span::styled(" ", Style::new().reversed()),
+    ]);
+
+    let mut texts = vec![];
+    if let Some(bindings) = component.get_bindings() {
+        texts.append(&mut binding_to_help(
+            bindings,
+            component.get_name(),
+            search,
+            &is_disabled,
+        ));
+    }
+    texts.append(&mut binding_to_help(
+        &GENERAL_BINDINGS,
+        "General",
+        search,
+        &is_disabled,
+    ));
+    texts.append(&mut binding_to_help(
+        &NAVIGATION_BINDINGS,
+        "Navigation",
+        search,
+        &is_disabled,
+    ));
+    if texts.is_empty() {
+        texts.push(Line::from("No matching binding".italic()));
+    }
+
+    let max_scroll = texts.len().saturating_sub(rects[1].height as usize);
+    *scroll = (*scroll).min(max_scroll);
 
     f.render_widget(Clear, area); //this clears out the background
     f.render_widget(block, area);
+    f.render_widget(Paragraph::new(search_line), rects[0]);
+    f.render_widget(Paragraph::new(texts).scroll((*scroll as u16, 0)), rects[1]);
+    if max_scroll > 0 {
+        let mut state = ScrollbarState::new(max_scroll).position(*scroll);
+        f.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight),
+            rects[1],
+            &mut state,
+        );
+    }
+}
 
-    if let Some(bindings) = component.get_bindings() {
-        let resource = binding_to_help(bindings, component.get_name());
-        f.render_widget(resource.block(column_block.clone()), columns[0]);
+// Bindings of the component and general ones with a single key, whose description fuzzy
+// matches the search
+pub(crate) fn palette_bindings<'a>(
+    component: &'a Component,
+    search: &str,
+) -> Vec<(&'a str, &'a str)> {
+    let filter = NameFilter::Fuzzy(search.to_string());
+    component
+        .get_bindings()
+        .unwrap_or_default()
+        .iter()
+        .chain(GENERAL_BINDINGS.iter())
+        .chain(NAVIGATION_BINDINGS.iter())
+        .filter(|(k, a)| parse_binding(k).is_some() && filter.matches(a))
+        .copied()
+        .collect()
+}
+
+pub(crate) fn palette_popup<F>(
+    f: &mut Frame<'_>,
+    bindings: &[(&str, &str)],
+    search: &str,
+    selected: usize,
+    is_disabled: F,
+) where
+    F: Fn(&str) -> bool,
+{
+    let area = centered_rect(80.min(f.size().width), 20.min(f.size().height), f.size());
+
+    let block = Block::default()
+        .title("Commands (type to search, Up/Down to select, Enter to run, ESC to close)")
+        .padding(Padding::new(1, 1, 0, 0))
+        .borders(Borders::ALL);
+    let inner = block.inner(area);
+
+    let rects = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(1)])
+        .split(inner);
+
+    let search_line = Line::from(vec![
+        Span::styled("> ", Style::new().bold()),
+        Span::raw(search.to_string()),
+        Span::styled(" ", Style::new().reversed()),
+    ]);
+
+    let mut texts: Vec<Line> = bindings
+        .iter()
+        .enumerate()
+        .map(|(i, (k, a))| {
+            let text = format!("{: <50} <{}>", a, k);
+            let style = if i == selected {
+                Style::new().reversed()
+            } else if is_disabled(k) {
+                Style::new().dark_gray()
+            } else {
+                Style::new()
+            };
+            Line::styled(text, style)
+        })
+        .collect();
+    if texts.is_empty() {
+        texts.push(Line::from("No matching command".italic()));
     }
+    // Keep the selected command visible
+    let scroll = selected.saturating_sub((rects[1].height as usize).saturating_sub(1));
 
-    let general = binding_to_help(&GENERAL_BINDINGS, "General");
-    f.render_widget(general.block(column_block.clone()), columns[1]);
+    f.render_widget(Clear, area); //this clears out the background
+    f.render_widget(block, area);
+    f.render_widget(Paragraph::new(search_line), rects[0]);
+    f.render_widget(Paragraph::new(texts).scroll((scroll as u16, 0)), rects[1]);
+}
 
-    let navigation = binding_to_help(&NAVIGATION_BINDINGS, "Navigation");
-    f.render_widget(navigation.block(column_block), columns[2]);
+pub(crate) fn error_log_screen(f: &mut Frame<'_>, errors: &VecDeque<(DateTime<Local>, String)>) {
+    let area = default_layout(f.size())[1];
+
+    let block = Block::default()
+        .title(format!("Errors ({})", errors.len()))
+        .padding(Padding::new(1, 1, 1, 1))
+        .borders(Borders::ALL);
+
+    let text: Vec<Line> = if errors.is_empty() {
+        vec![Line::from("No errors")]
+    } else {
+        errors
+            .iter()
+            .flat_map(|(time, msg)| {
+                msg.lines().enumerate().map(move |(i, line)| {
+                    let prefix = if i == 0 {
+                        time.format("%H:%M:%S").to_string()
+                    } else {
+                        " ".repeat(8)
+                    };
+                    Line::from(vec![
+                        Span::styled(prefix, Style::new().red()),
+                        Span::raw(format!(" {}", line)),
+                    ])
+                })
+            })
+            .collect()
+    };
+
+    f.render_widget(Clear, area); //this clears out the background
+    f.render_widget(
+        Paragraph::new(text).wrap(Wrap { trim: false }).block(block),
+        area,
+    );
 }
 
-fn binding_to_help<'a, 'b, T>(bindings: T, title: &'static str) -> Paragraph<'a>
+// Section lines for the bindings whose description matches the search,
+// nothing at all if none matches
+fn binding_to_help<'a, 'b, T, F>(
+    bindings: T,
+    title: &'static str,
+    search: &str,
+    is_disabled: &F,
+) -> Vec<Line<'a>>
 where
     T: IntoIterator<Item = &'b (&'b str, &'b str)>,
+    F: Fn(&str) -> bool,
     'b: 'a,
 {
-    let title = vec![Line::from(title.bold()), Line::from("")];
-
-    let texts: Vec<Line<'a>> = title
+    let search = search.to_lowercase();
+    let mut texts: Vec<Line<'a>> = bindings
         .into_iter()
-        .chain(
-            bindings
-                .into_iter()
-                .map(|(k, a)| Line::from(format!("{: <10} : {}", format!("<{}>", k), a))),
-        )
+        .filter(|(_, a)| a.to_lowercase().contains(&search))
+        .map(|(k, a)| {
+            let text = format!("{: <10} : {}", format!("<{}>", k), a);
+            if is_disabled(k) {
+                Line::styled(format!("{} (read-only)", text), Style::new().dark_gray())
+            } else {
+                Line::from(text)
+            }
+        })
         .collect();
-    Paragraph::new(texts)
+    if !texts.is_empty() {
+        texts.insert(0, Line::from(title.bold()));
+        texts.push(Line::from(""));
+    }
+    texts
+}
+
+// Key event matching a binding as displayed in the help, e.g. "ctrl+d", "Enter" or "F1"
+pub(crate) fn parse_binding(binding: &str) -> Option<KeyEvent> {
+    let (modifiers, key) = match binding.split_once('+') {
+        Some(("ctrl", key)) => (KeyModifiers::CONTROL, key),
+        Some(_) => return None,
+        None => (KeyModifiers::NONE, binding),
+    };
+    let code = match key {
+        "Enter" => KeyCode::Enter,
+        "Space" => KeyCode::Char(' '),
+        "ESC" => KeyCode::Esc,
+        "Tab" => KeyCode::Tab,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        _ if key.starts_with('F') && key.len() > 1 => KeyCode::F(key[1..].parse().ok()?),
+        _ => {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => return None,
+            }
+        }
+    };
+    Some(KeyEvent::new(code, modifiers))
 }
 
 pub fn initialize_panic_handler() -> Result<()> {
@@ -284,6 +906,40 @@ pub fn initialize_panic_handler() -> Result<()> {
     Ok(())
 }
 
+// Restores the terminal and closes the runtime connection (e.g. the SSH tunnel) when the
+// process is terminated, otherwise the terminal would stay in raw mode
+#[cfg(unix)]
+pub fn initialize_signal_handler() -> Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut sigterm = signal(SignalKind::terminate())?;
+    let mut sighup = signal(SignalKind::hangup())?;
+    tokio::spawn(async move {
+        let signal = tokio::select! {
+            _ = sigterm.recv() => libc::SIGTERM,
+            _ = sighup.recv() => libc::SIGHUP,
+        };
+        log::info!("Received signal {}, exiting", signal);
+        if let Ok(mut t) = crate::tui::Tui::new() {
+            if let Err(r) = t.exit() {
+                error!("Unable to exit Terminal: {:?}", r);
+            }
+        }
+        let _ = tokio::time::timeout(
+            std::time::Duration::from_secs(1),
+            crate::runtime::shutdown(),
+        )
+        .await;
+        std::process::exit(128 + signal);
+    });
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn initialize_signal_handler() -> Result<()> {
+    Ok(())
+}
+
 pub fn get_data_dir() -> PathBuf {
     let directory = if let Some(s) = DATA_FOLDER.clone() {
         s
@@ -295,18 +951,45 @@ pub fn get_data_dir() -> PathBuf {
     directory
 }
 
-pub fn initialize_logging() -> Result<()> {
+// Uses the OSC 52 escape sequence so it also works through ssh and tmux
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    let mut stderr = std::io::stderr();
+    write!(stderr, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+    stderr.flush()?;
+    Ok(())
+}
+
+pub fn get_config_dir() -> PathBuf {
+    let directory = if let Some(s) = CONFIG_FOLDER.clone() {
+        s
+    } else if let Some(proj_dirs) = project_directory() {
+        proj_dirs.config_local_dir().to_path_buf()
+    } else {
+        PathBuf::from(".").join(".config")
+    };
+    directory
+}
+
+pub fn initialize_logging(level: Option<tracing::Level>) -> Result<()> {
     let directory = get_data_dir();
     std::fs::create_dir_all(directory.clone())?;
 
-    let log_path = directory.join(LOG_FILE.clone());
-    let log_file = std::fs::File::create(log_path)?;
+    // One file per day (e.g. doggy.2024-01-31.log), the oldest ones are removed
+    let log_file = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(env!("CARGO_PKG_NAME"))
+        .filename_suffix("log")
+        .max_log_files(config::get().logging.retention.max(1))
+        .build(directory)?;
 
     std::env::set_var(
         "RUST_LOG",
-        std::env::var("RUST_LOG")
-            .or_else(|_| std::env::var(LOG_ENV.clone()))
-            .unwrap_or_else(|_| format!("{}=info", env!("CARGO_CRATE_NAME"))),
+        match level {
+            Some(level) => format!("{}={}", env!("CARGO_CRATE_NAME"), level),
+            None => std::env::var("RUST_LOG")
+                .or_else(|_| std::env::var(LOG_ENV.clone()))
+                .unwrap_or_else(|_| format!("{}=info", env!("CARGO_CRATE_NAME"))),
+        },
     );
 
     // The SubscriberExt and SubscriberInitExt traits are needed to extend the
@@ -324,14 +1007,36 @@ pub fn initialize_logging() -> Result<()> {
 
     #[cfg(feature = "otel")]
     let tracing_registry = {
-        // Allows you to pass along context (i.e., trace IDs) across services
-        global::set_text_map_propagator(opentelemetry_jaeger::Propagator::new());
-        // Sets up the machinery needed to export data to Jaeger
-        // There are other OTel crates that provide pipelines for the vendors
-        // mentioned earlier.
-        let tracer = opentelemetry_jaeger::new_pipeline()
-            .with_service_name("doggy")
-            .install_simple()?;
+        let telemetry = &config::get().telemetry;
+        let tracer = match telemetry.exporter {
+            config::TelemetryExporter::Jaeger => {
+                // Allows you to pass along context (i.e., trace IDs) across services
+                global::set_text_map_propagator(opentelemetry_jaeger::Propagator::new());
+                // Sets up the machinery needed to export data to Jaeger
+                let pipeline = opentelemetry_jaeger::new_pipeline()
+                    .with_service_name(telemetry.service_name.as_str());
+                match &telemetry.endpoint {
+                    Some(endpoint) => pipeline.with_agent_endpoint(endpoint.as_str()),
+                    None => pipeline,
+                }
+                .install_simple()?
+            }
+            config::TelemetryExporter::Otlp => {
+                global::set_text_map_propagator(TraceContextPropagator::new());
+                let exporter = opentelemetry_otlp::new_exporter().tonic().with_env();
+                let exporter = match &telemetry.endpoint {
+                    Some(endpoint) => exporter.with_endpoint(endpoint.as_str()),
+                    None => exporter,
+                };
+                opentelemetry_otlp::new_pipeline()
+                    .tracing()
+                    .with_exporter(exporter)
+                    .with_trace_config(trace::config().with_resource(Resource::new(vec![
+                        KeyValue::new("service.name", telemetry.service_name.clone()),
+                    ])))
+                    .install_batch(opentelemetry::runtime::Tokio)?
+            }
+        };
 
         // Create a tracing layer with the configured tracer
         let opentelemetry = tracing_opentelemetry::layer().with_tracer(tracer);
@@ -362,3 +1067,65 @@ impl Age for i64 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subslice_of_multibyte_suggestion() {
+        // The suggestion completion is rendered from the cursor, counted in chars
+        let suggestion = "répertoires";
+        assert_eq!(safe_subslice(suggestion, 2, usize::MAX), "pertoires");
+        assert_eq!(safe_subslice(suggestion, 1, 3), "ép");
+        assert_eq!(safe_subslice("日本語", 1, 2), "本");
+        assert_eq!(safe_subslice(suggestion, 11, usize::MAX), "");
+        assert_eq!(safe_subslice(suggestion, 42, usize::MAX), "");
+        assert_eq!(safe_subslice(suggestion, 5, 2), "");
+    }
+
+    #[test]
+    fn byte_index_of_multibyte() {
+        assert_eq!(byte_index("é1", 0), 0);
+        assert_eq!(byte_index("é1", 1), 2);
+        assert_eq!(byte_index("é1", 2), 3);
+        assert_eq!(byte_index("é1", 5), 3);
+    }
+
+    #[tokio::test]
+    async fn delete_all_reports_failures() {
+        let ids = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let (count, errors) = delete_all(ids, |id| async move {
+            if id == "b" {
+                Err(color_eyre::eyre::eyre!("in use"))
+            } else {
+                Ok(())
+            }
+        })
+        .await;
+        assert_eq!(count, 3);
+        assert_eq!(errors, vec!["b: in use".to_string()]);
+    }
+
+    #[test]
+    fn selection_follows_listing() {
+        let mut selected = HashSet::new();
+        let (a, b) = ("a".to_string(), "b".to_string());
+        assert!(toggle_selection(&mut selected, Some(&a)));
+        assert!(toggle_selection(&mut selected, Some(&b)));
+        assert!(!toggle_selection(&mut selected, None));
+        retain_listed(&mut selected, [&b].into_iter());
+        assert_eq!(selected, HashSet::from([b.clone()]));
+        assert!(toggle_selection(&mut selected, Some(&b)));
+        assert!(selected.is_empty());
+    }
+
+    #[test]
+    fn short_ids() {
+        assert_eq!(short_id("0123456789abcdef"), "0123456789ab");
+        assert_eq!(short_id("0123456789ab"), "0123456789ab");
+        assert_eq!(short_id("abc"), "abc");
+        assert_eq!(short_id(""), "");
+        assert_eq!(short_id("ééééééééééééé"), "éééééééééééé");
+    }
+}